@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// Marks a place in a group without a creature
+const EMPTY: u8 = b'x';
+
+#[derive(Debug)]
+pub enum RulesError {
+    /// The bonus table has no entry for groups of this many creatures
+    MissingBonus(usize),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RulesError::MissingBonus(n) => write!(f, "no bonus for groups of {n} creatures"),
+        }
+    }
+}
+
+/// Describes how many potions are needed to fight creatures
+pub struct Rules {
+    /// The number of potions needed for each creature
+    costs: [u64; 256],
+
+    /// Extra potions needed per group, indexed by the number of creatures in
+    /// the group
+    bonuses: Vec<u64>,
+}
+
+impl Rules {
+    /// Create rules from a table of creature costs and a table of bonuses.
+    /// Creatures not listed in the cost table need no potions.
+    pub fn new(costs: &[(u8, u64)], bonuses: &[u64]) -> Self {
+        let mut table = [0; 256];
+        for &(c, cost) in costs {
+            table[c as usize] = cost;
+        }
+        Self {
+            costs: table,
+            bonuses: bonuses.to_vec(),
+        }
+    }
+
+    /// Calculate the number of potions needed for a single group
+    fn group_potions(&self, group: &[u8]) -> u64 {
+        let creatures = group.iter().filter(|&&c| c != EMPTY).count();
+        group.iter().map(|&c| self.costs[c as usize]).sum::<u64>() + self.bonuses[creatures]
+    }
+
+    /// Calculate the number of potions needed for all creatures if they
+    /// attack in groups of the given size. The last group may be smaller.
+    /// Fails if the bonus table does not cover groups of that size.
+    pub fn potions(&self, creatures: &[u8], group_size: usize) -> Result<u64, RulesError> {
+        assert!(group_size > 0, "Group size must be positive");
+        if group_size >= self.bonuses.len() {
+            return Err(RulesError::MissingBonus(group_size));
+        }
+        Ok(creatures
+            .chunks(group_size)
+            .map(|g| self.group_potions(g))
+            .sum())
+    }
+}
@@ -1,68 +1,26 @@
 use std::fs;
 
-fn creature_to_potion(c: u8) -> u64 {
-    match c {
-        b'B' => 1,
-        b'C' => 3,
-        b'D' => 5,
-        _ => 0,
-    }
-}
+use battle::Rules;
 
-fn main() {
-    // part 1
-    let input =
-        fs::read_to_string("everybody_codes_e2024_q01_p1.txt").expect("Could not read file");
-    let bytes = input.trim().as_bytes();
+mod battle;
 
-    let mut total = 0;
-    let mut i = 0;
-    while i < bytes.len() {
-        total += creature_to_potion(bytes[i]);
-        i += 1;
-    }
-    println!("{}", total);
+/// The number of potions needed for each creature
+const COSTS: [(u8, u64); 3] = [(b'B', 1), (b'C', 3), (b'D', 5)];
 
-    // part 2
-    let input =
-        fs::read_to_string("everybody_codes_e2024_q01_p2.txt").expect("Could not read file");
-    let bytes = input.trim().as_bytes();
+/// Extra potions needed per group, indexed by the number of creatures in it
+const BONUSES: [u64; 4] = [0, 0, 2, 6];
 
-    let mut total = 0;
-    let mut i = 0;
-    while i < bytes.len() - 1 {
-        let c1 = bytes[i];
-        let c2 = bytes[i + 1];
-        total += creature_to_potion(c1);
-        total += creature_to_potion(c2);
-        if ![c1, c2].contains(&b'x') {
-            total += 2;
-        }
-        i += 2;
-    }
-    println!("{}", total);
+fn main() {
+    let rules = Rules::new(&COSTS, &BONUSES);
 
-    // part 3
-    let input =
-        fs::read_to_string("everybody_codes_e2024_q01_p3.txt").expect("Could not read file");
-    let bytes = input.trim().as_bytes();
+    for part in [1, 2, 3] {
+        let input = fs::read_to_string(format!("everybody_codes_e2024_q01_p{}.txt", part))
+            .expect("Could not read file");
 
-    let mut total = 0;
-    let mut i = 0;
-    while i < bytes.len() - 2 {
-        let c1 = bytes[i];
-        let c2 = bytes[i + 1];
-        let c3 = bytes[i + 2];
-        total += creature_to_potion(c1);
-        total += creature_to_potion(c2);
-        total += creature_to_potion(c3);
-        let xs = [c1, c2, c3].into_iter().filter(|c| *c == b'x').count();
-        if xs == 0 {
-            total += 6;
-        } else if xs == 1 {
-            total += 2;
-        }
-        i += 3;
+        // in each part, the creatures attack in groups of `part`
+        let potions = rules
+            .potions(input.trim().as_bytes(), part)
+            .unwrap_or_else(|e| panic!("Invalid rules: {e}"));
+        println!("{potions}");
     }
-    println!("{}", total);
 }
@@ -1,74 +1,36 @@
-use std::collections::{HashMap, VecDeque};
 use std::fs;
 
+use tree::{NameStyle, Tree};
+
+mod tree;
+
 fn main() {
     for part in [1, 2, 3] {
         let input = fs::read_to_string(format!("everybody_codes_e2024_q06_p{}.txt", part))
             .expect("Could not read file");
-        let lines = input.lines().collect::<Vec<_>>();
+        let tree = Tree::parse(&input).unwrap_or_else(|e| panic!("Invalid tree: {e}"));
 
-        // read map
-        let mut map: HashMap<&str, Vec<&str>> = HashMap::new();
-        for l in lines {
-            let (left, right) = l.split_once(":").unwrap();
-            for r in right.split(",") {
-                map.entry(left).or_default().push(r);
+        if cfg!(feature = "visualize") {
+            for name in tree.unreachable() {
+                eprintln!("Branch {name} is unreachable");
             }
         }
 
-        // flood fill to find all paths
-        let mut paths = Vec::new();
-        let mut queue = VecDeque::new();
-        queue.push_back(("RR", Vec::new())); // RR is the root
-
-        while let Some((cur, mut path)) = queue.pop_front() {
-            if part == 3 && (cur == "ANT" || cur == "BUG") {
-                // skip ants and bugs in part 3
-                continue;
-            }
+        // part 1 uses full names, parts 2 and 3 only the first letters
+        let style = if part == 1 {
+            NameStyle::Full
+        } else {
+            NameStyle::FirstLetter
+        };
 
-            path.push(cur);
-            if cur == "@" {
-                // reached a fruit
-                paths.push(path);
-                continue;
-            }
-
-            // add all neighbors to queue
-            if let Some(ns) = map.get(cur) {
-                for n in ns {
-                    queue.push_back((n, path.clone()));
-                }
-            }
-        }
+        // skip ants and bugs in part 3
+        let blocklist: &[&str] = if part == 3 { &["ANT", "BUG"] } else { &[] };
 
         // find the only path that has a unique length
-        paths.sort_unstable_by_key(|p| p.len());
-        let mut i = 0;
-        let most_powerful_branch = loop {
-            let pl = paths[i].len();
-            let prevl = if i > 0 { paths[i - 1].len() } else { pl };
-            let nextl = if i < paths.len() - 1 {
-                paths[i + 1].len()
-            } else {
-                pl
-            };
-            if pl != prevl && pl != nextl {
-                break &paths[i];
-            }
-            i += 1;
-        };
-
-        if part == 1 {
-            println!("{}", most_powerful_branch.join(""));
-        } else {
-            println!(
-                "{}",
-                most_powerful_branch
-                    .iter()
-                    .map(|s| s.chars().next().unwrap())
-                    .collect::<String>()
-            );
-        }
+        let most_powerful_branch = tree
+            .unique_path(style, blocklist)
+            .unwrap_or_else(|e| panic!("Invalid tree: {e}"))
+            .expect("No path with a unique length");
+        println!("{}", most_powerful_branch);
     }
 }
@@ -0,0 +1,307 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// The name of the root node
+pub const ROOT: &str = "RR";
+
+/// The name of a fruit node
+pub const FRUIT: &str = "@";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NodeKind {
+    Branch,
+    Fruit,
+}
+
+#[derive(Debug)]
+pub struct Node<'a> {
+    pub name: &'a str,
+    pub kind: NodeKind,
+    pub children: Vec<usize>,
+}
+
+/// Specifies how node names should be joined when a path is rendered
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NameStyle {
+    /// Use the full name of each node
+    Full,
+
+    /// Only use the first letter of each node's name
+    FirstLetter,
+}
+
+#[derive(Debug)]
+pub enum TreeError {
+    /// A line did not have the form `NAME:CHILD,CHILD,...`
+    InvalidLine(String),
+
+    /// The root node `RR` was not defined
+    MissingRoot,
+
+    /// The tree contains a cycle. The names of the nodes forming the cycle
+    /// are given in order, starting and ending with the same node.
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeError::InvalidLine(l) => write!(f, "invalid line `{l}`"),
+            TreeError::MissingRoot => write!(f, "root node `{ROOT}` is missing"),
+            TreeError::Cycle(names) => write!(f, "cycle detected: {}", names.join(" -> ")),
+        }
+    }
+}
+
+/// A tree of branches and fruits. Branches are identified by their names.
+/// Every fruit is a separate leaf node, even though all fruits share the same
+/// name `@`.
+pub struct Tree<'a> {
+    nodes: Vec<Node<'a>>,
+    root: usize,
+}
+
+impl<'a> Tree<'a> {
+    /// Parse a tree from lines of the form `RR:A,B,@`. Returns an error if a
+    /// line is malformed or if the root is missing. Cycles are only detected
+    /// when paths are collected, so that they can be avoided with a
+    /// blocklist.
+    pub fn parse(input: &'a str) -> Result<Self, TreeError> {
+        let mut nodes: Vec<Node<'a>> = Vec::new();
+        let mut ids: HashMap<&str, usize> = HashMap::new();
+
+        fn get_or_insert<'a>(
+            name: &'a str,
+            nodes: &mut Vec<Node<'a>>,
+            ids: &mut HashMap<&'a str, usize>,
+        ) -> usize {
+            if name == FRUIT {
+                // every fruit is a node of its own
+                nodes.push(Node {
+                    name,
+                    kind: NodeKind::Fruit,
+                    children: Vec::new(),
+                });
+                return nodes.len() - 1;
+            }
+            *ids.entry(name).or_insert_with(|| {
+                nodes.push(Node {
+                    name,
+                    kind: NodeKind::Branch,
+                    children: Vec::new(),
+                });
+                nodes.len() - 1
+            })
+        }
+
+        for l in input.lines() {
+            let Some((left, right)) = l.split_once(':') else {
+                return Err(TreeError::InvalidLine(l.to_string()));
+            };
+            if left.is_empty() || left == FRUIT {
+                return Err(TreeError::InvalidLine(l.to_string()));
+            }
+            let parent = get_or_insert(left, &mut nodes, &mut ids);
+            for r in right.split(',') {
+                if r.is_empty() {
+                    return Err(TreeError::InvalidLine(l.to_string()));
+                }
+                let child = get_or_insert(r, &mut nodes, &mut ids);
+                nodes[parent].children.push(child);
+            }
+        }
+
+        let Some(&root) = ids.get(ROOT) else {
+            return Err(TreeError::MissingRoot);
+        };
+
+        Ok(Tree { nodes, root })
+    }
+
+    /// Perform a depth-first search from the root and return an error if we
+    /// reach a node that is currently on the stack. Blocked nodes are not
+    /// expanded, so cycles through them are not reported.
+    fn check_cycles(&self, blocked: impl Fn(usize) -> bool) -> Result<(), TreeError> {
+        if blocked(self.root) {
+            return Ok(());
+        }
+
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        enum State {
+            Unvisited,
+            OnStack,
+            Done,
+        }
+
+        let mut state = vec![State::Unvisited; self.nodes.len()];
+        let mut stack = vec![(self.root, 0)];
+        state[self.root] = State::OnStack;
+
+        while let Some((n, i)) = stack.last_mut() {
+            let n = *n;
+            if let Some(&c) = self.nodes[n].children.get(*i) {
+                *i += 1;
+                if blocked(c) {
+                    continue;
+                }
+                match state[c] {
+                    State::Unvisited => {
+                        state[c] = State::OnStack;
+                        stack.push((c, 0));
+                    }
+                    State::OnStack => {
+                        let start = stack.iter().position(|&(s, _)| s == c).unwrap();
+                        let mut names = stack[start..]
+                            .iter()
+                            .map(|&(s, _)| self.nodes[s].name.to_string())
+                            .collect::<Vec<_>>();
+                        names.push(self.nodes[c].name.to_string());
+                        return Err(TreeError::Cycle(names));
+                    }
+                    State::Done => {}
+                }
+            } else {
+                state[n] = State::Done;
+                stack.pop();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the names of all branches that cannot be reached from the root
+    pub fn unreachable(&self) -> Vec<&'a str> {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut stack = vec![self.root];
+        seen[self.root] = true;
+        while let Some(n) = stack.pop() {
+            for &c in &self.nodes[n].children {
+                if !seen[c] {
+                    seen[c] = true;
+                    stack.push(c);
+                }
+            }
+        }
+
+        self.nodes
+            .iter()
+            .zip(seen)
+            .filter(|(n, s)| !s && n.kind == NodeKind::Branch)
+            .map(|(n, _)| n.name)
+            .collect()
+    }
+
+    /// Collect all paths from the root to a fruit and group them by their
+    /// length. Paths leading through a node whose name is in `blocklist` are
+    /// skipped. Returns an error if the remaining tree contains a cycle
+    /// reachable from the root.
+    pub fn fruit_paths(
+        &self,
+        blocklist: &[&str],
+    ) -> Result<BTreeMap<usize, Vec<Vec<usize>>>, TreeError> {
+        let mut result: BTreeMap<usize, Vec<Vec<usize>>> = BTreeMap::new();
+        let blocked = |n: usize| blocklist.contains(&self.nodes[n].name);
+        self.check_cycles(blocked)?;
+        if blocked(self.root) {
+            return Ok(result);
+        }
+
+        // depth-first search with a single shared path instead of cloning
+        // the path for every node
+        let mut path = vec![self.root];
+        let mut stack = vec![0];
+        while let Some(i) = stack.last_mut() {
+            let n = path[path.len() - 1];
+            if let Some(&c) = self.nodes[n].children.get(*i) {
+                *i += 1;
+                if blocked(c) {
+                    continue;
+                }
+                path.push(c);
+                if self.nodes[c].kind == NodeKind::Fruit {
+                    result.entry(path.len()).or_default().push(path.clone());
+                    path.pop();
+                } else {
+                    stack.push(0);
+                }
+            } else {
+                stack.pop();
+                path.pop();
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Render a path according to the given name style
+    pub fn path_to_string(&self, path: &[usize], style: NameStyle) -> String {
+        path.iter()
+            .map(|&n| match style {
+                NameStyle::Full => self.nodes[n].name,
+                NameStyle::FirstLetter => {
+                    let name = self.nodes[n].name;
+                    &name[..name.chars().next().map_or(0, char::len_utf8)]
+                }
+            })
+            .collect()
+    }
+
+    /// Find the only path from the root to a fruit whose length is unique
+    /// and render it according to the given name style. Paths through nodes
+    /// in `blocklist` are ignored. Returns `None` if there is no such path.
+    pub fn unique_path(
+        &self,
+        style: NameStyle,
+        blocklist: &[&str],
+    ) -> Result<Option<String>, TreeError> {
+        Ok(self
+            .fruit_paths(blocklist)?
+            .into_values()
+            .find(|paths| paths.len() == 1)
+            .map(|paths| self.path_to_string(&paths[0], style)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "RR:A,B,C\nA:D,E\nB:F,@\nC:G,H\nD:@\nE:@\nF:@\nG:@\nH:@";
+
+    #[test]
+    fn unique_path() {
+        let tree = Tree::parse(EXAMPLE).unwrap();
+        assert_eq!(
+            tree.unique_path(NameStyle::Full, &[]).unwrap().as_deref(),
+            Some("RRB@")
+        );
+
+        // blocking `B` removes the short path, so all paths have length 4
+        assert_eq!(tree.unique_path(NameStyle::Full, &["B"]).unwrap(), None);
+        assert!(tree.fruit_paths(&["RR"]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn cycles() {
+        let tree = Tree::parse("RR:A,@\nA:B\nB:A,@").unwrap();
+        let Err(TreeError::Cycle(names)) = tree.fruit_paths(&[]) else {
+            panic!("Cycle not detected");
+        };
+        assert_eq!(names, ["A", "B", "A"]);
+
+        // cycles through blocked or unreachable branches do not matter
+        assert_eq!(tree.fruit_paths(&["B"]).unwrap().len(), 1);
+        let tree = Tree::parse("RR:@\nX:Y\nY:X").unwrap();
+        assert_eq!(
+            tree.unique_path(NameStyle::Full, &[]).unwrap().as_deref(),
+            Some("RR@")
+        );
+    }
+
+    #[test]
+    fn unreachable_branches() {
+        let tree = Tree::parse("RR:A,@\nA:@\nX:Y,@").unwrap();
+        assert_eq!(tree.unreachable(), ["X", "Y"]);
+        assert!(Tree::parse(EXAMPLE).unwrap().unreachable().is_empty());
+    }
+}
@@ -1,7 +1,10 @@
-use permutations::permutations_lexicographic;
+use optimizer::RaceModel;
 use std::fs;
+use track::{Direction, Track};
 
+mod optimizer;
 mod permutations;
+mod track;
 
 pub const DIRS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
 
@@ -31,44 +34,16 @@ fn parse(input: &str) -> Vec<(&str, Vec<Action>)> {
         .collect()
 }
 
-fn parse_track(mut plan: Vec<Vec<u8>>) -> Vec<Action> {
-    let width = plan[0].len();
-    let height = plan.len();
+fn parse_track(filename: &str) -> Vec<Action> {
+    let input = fs::read_to_string(filename).expect("Could not read file");
+    let track = Track::parse(&input, Direction::Clockwise)
+        .unwrap_or_else(|e| panic!("Invalid track {filename}: {e}"));
 
-    let mut pos = (1, 0);
-    let mut result = Vec::new();
-    loop {
-        let c = plan[pos.1][pos.0];
-        let action = match c {
-            b'+' => Action::Inc,
-            b'-' => Action::Dec,
-            b'=' | b'S' => Action::Maintain,
-            _ => panic!(),
-        };
-        result.push(action);
-
-        if c == b'S' {
-            break;
-        }
-
-        plan[pos.1][pos.0] = b' ';
-
-        for (dx, dy) in DIRS {
-            let nx = (pos.0 as i32) + dx;
-            let ny = (pos.1 as i32) + dy;
-            if nx >= 0
-                && ny >= 0
-                && nx < width as i32
-                && ny < height as i32
-                && plan[ny as usize][nx as usize] != b' '
-            {
-                pos = (nx as usize, ny as usize);
-                break;
-            }
-        }
+    if cfg!(feature = "visualize") {
+        eprintln!("{}\n", track.render());
     }
 
-    result
+    track.actions()
 }
 
 fn race(actions: &[Action], track: &[Action], rounds: usize) -> u64 {
@@ -110,12 +85,7 @@ fn main() {
     // part 2
     let input2 = fs::read_to_string("everybody_codes_e2024_q07_p2.txt").unwrap();
     let charriots2 = parse(&input2);
-    let track2 = fs::read_to_string("track_p2.txt")
-        .unwrap()
-        .lines()
-        .map(|l| l.as_bytes().to_vec())
-        .collect::<Vec<_>>();
-    let track2 = parse_track(track2);
+    let track2 = parse_track("track_p2.txt");
     let mut scores2 = charriots2
         .into_iter()
         .map(|(name, actions)| (name, race(&actions, &track2, 10)))
@@ -129,20 +99,7 @@ fn main() {
     // part 3
     let input3 = fs::read_to_string("everybody_codes_e2024_q07_p3.txt").unwrap();
     let charriots3 = parse(&input3);
-    let track3 = fs::read_to_string("track_p3.txt")
-        .unwrap()
-        .lines()
-        .map(|l| l.as_bytes().to_vec())
-        .collect::<Vec<_>>();
-    let track3 = parse_track(track3);
-
-    // The race doesn't need to take 2024 rounds. Since each plan is 11 actions
-    // long and the power apparently never drops to zero, the amount of essence
-    // we can gather will repeat after 11 rounds. Further, since 2024 is
-    // divisible by 11, the winner will already have been decided at that point.
-    let rounds3 = 11; // instead of 2024
-
-    let score_a = race(&charriots3.into_iter().next().unwrap().1, &track3, rounds3);
+    let track3 = parse_track("track_p3.txt");
 
     let template = vec![
         Action::Inc,
@@ -157,12 +114,10 @@ fn main() {
         Action::Maintain,
         Action::Maintain,
     ];
-    let mut total = 0;
-    for t in permutations_lexicographic(&template) {
-        let score_b = race(&t, &track3, rounds3);
-        if score_b > score_a {
-            total += 1;
-        }
-    }
-    println!("{}", total);
+    let rival = &charriots3[0].1;
+
+    // instead of simulating all 2024 loops for every permutation of the
+    // template, calculate the essence analytically and only count plans
+    let model = RaceModel::new(&track3, 2024, &template);
+    println!("{}", model.count_winning(rival));
 }
@@ -0,0 +1,283 @@
+use crate::permutations::permutations_lexicographic;
+use crate::{Action, race};
+
+/// The amount of power every chariot starts with
+const INITIAL_POWER: i64 = 10;
+
+impl Action {
+    /// The change in power caused by this action
+    fn delta(self) -> i64 {
+        match self {
+            Action::Inc => 1,
+            Action::Dec => -1,
+            Action::Maintain => 0,
+        }
+    }
+}
+
+/// Greatest common divisor
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Least common multiple
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+/// Count how often each action occurs in a plan. Returns a tuple of the
+/// number of increments, decrements and maintains.
+fn count_actions(plan: &[Action]) -> (usize, usize, usize) {
+    let mut counts = (0, 0, 0);
+    for a in plan {
+        match a {
+            Action::Inc => counts.0 += 1,
+            Action::Dec => counts.1 += 1,
+            Action::Maintain => counts.2 += 1,
+        }
+    }
+    counts
+}
+
+/// Number of distinct arrangements of a multiset with the given counts
+fn multinomial(counts: &[usize]) -> u64 {
+    let mut result = 1u64;
+    let mut n = 0u64;
+    for &c in counts {
+        for k in 1..=c as u64 {
+            n += 1;
+            result = result * n / k;
+        }
+    }
+    result
+}
+
+/// An analytical model of a race on a given track over a given number of
+/// loops for all plans that are permutations of a given template.
+///
+/// Without the power ever dropping to zero, the essence a plan gathers is a
+/// linear function of its actions: every `=` segment passed at step `j` (out
+/// of `n` steps in total) contributes the plan's action at index `j % len` to
+/// the power of all remaining `n - j` steps. Since the combination of track
+/// and plan repeats after `lcm(track.len(), plan.len())` steps, the weight of
+/// each plan index can be calculated in closed form without simulating all
+/// loops.
+pub struct RaceModel<'a> {
+    track: &'a [Action],
+    loops: usize,
+    template: (usize, usize, usize),
+
+    /// The essence gathered by a plan consisting only of `=` actions
+    base: i64,
+
+    /// The contribution of each plan index to the total essence
+    weights: Vec<i64>,
+
+    /// `true` if no permutation of the template can ever let the power drop
+    /// below zero, which means the linear model is exact
+    exact: bool,
+}
+
+impl<'a> RaceModel<'a> {
+    pub fn new(track: &'a [Action], loops: usize, template: &[Action]) -> Self {
+        let plan_len = template.len();
+        let period = lcm(plan_len, track.len());
+        let n = (track.len() * loops) as i64;
+
+        let mut base = INITIAL_POWER * n;
+        let mut weights = vec![0; plan_len];
+        for r in 0..period.min(n as usize) {
+            // all steps j = m * period + r with j < n
+            let r = r as i64;
+            let q = (n - r + period as i64 - 1) / period as i64;
+            let w = q * (n - r) - period as i64 * q * (q - 1) / 2;
+            match track[r as usize % track.len()] {
+                Action::Maintain => weights[r as usize % plan_len] += w,
+                a => base += a.delta() * w,
+            }
+        }
+
+        let mut model = Self {
+            track,
+            loops,
+            template: count_actions(template),
+            base,
+            weights,
+            exact: false,
+        };
+        model.exact = model.min_power() >= 0;
+        model
+    }
+
+    /// Calculate a lower bound for the power over all steps of the race and
+    /// all permutations of the template. At each step, the worst plan puts
+    /// its decrements at the indices that have been used most often so far
+    /// and its increments at the ones used least often.
+    fn min_power(&self) -> i64 {
+        let plan_len = self.weights.len();
+        let (inc, dec, _) = self.template;
+        let mut fixed = INITIAL_POWER;
+        let mut used = vec![0i64; plan_len];
+        let mut sorted = vec![0i64; plan_len];
+        let mut min = INITIAL_POWER;
+        for j in 0..self.track.len() * self.loops {
+            match self.track[j % self.track.len()] {
+                Action::Maintain => used[j % plan_len] += 1,
+                a => fixed += a.delta(),
+            }
+            sorted.copy_from_slice(&used);
+            sorted.sort_unstable();
+            let worst =
+                sorted[..inc].iter().sum::<i64>() - sorted[plan_len - dec..].iter().sum::<i64>();
+            min = min.min(fixed + worst);
+        }
+        min
+    }
+
+    /// Calculate the essence the given plan gathers. Falls back to simulating
+    /// the race if the plan is not a permutation of the template or if the
+    /// power might drop to zero.
+    pub fn essence(&self, plan: &[Action]) -> u64 {
+        if !self.exact || count_actions(plan) != self.template {
+            return race(plan, self.track, self.loops);
+        }
+        let e = self.base
+            + plan
+                .iter()
+                .zip(&self.weights)
+                .map(|(a, w)| a.delta() * w)
+                .sum::<i64>();
+        e as u64
+    }
+
+    /// Count how many distinct permutations of the template gather more
+    /// essence than the given rival plan. Performs a branch-and-bound search
+    /// over the plan indices ordered by descending weight and counts whole
+    /// subtrees at once as soon as they are guaranteed to win or lose.
+    pub fn count_winning(&self, rival: &[Action]) -> u64 {
+        if !self.exact {
+            return self.count_winning_brute_force(rival);
+        }
+
+        let target = self.essence(rival) as i64 - self.base;
+
+        let mut sorted = self.weights.clone();
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+        let mut prefix = vec![0; sorted.len() + 1];
+        for (i, w) in sorted.iter().enumerate() {
+            prefix[i + 1] = prefix[i] + w;
+        }
+
+        let search = Search {
+            weights: &sorted,
+            prefix,
+            target,
+        };
+        let (inc, dec, maintain) = self.template;
+        search.count(0, 0, inc, dec, maintain)
+    }
+
+    /// Count winning plans by enumerating all permutations of the template
+    /// and simulating the race for each of them
+    pub fn count_winning_brute_force(&self, rival: &[Action]) -> u64 {
+        let score_a = race(rival, self.track, self.loops);
+        let mut template = Vec::new();
+        let (inc, dec, maintain) = self.template;
+        template.extend(std::iter::repeat_n(Action::Inc, inc));
+        template.extend(std::iter::repeat_n(Action::Dec, dec));
+        template.extend(std::iter::repeat_n(Action::Maintain, maintain));
+
+        let mut total = 0;
+        for t in permutations_lexicographic(&template) {
+            if race(&t, self.track, self.loops) > score_a {
+                total += 1;
+            }
+        }
+        total
+    }
+}
+
+/// A branch-and-bound search over plan weights sorted in descending order
+struct Search<'a> {
+    weights: &'a [i64],
+
+    /// Prefix sums of the weights
+    prefix: Vec<i64>,
+
+    /// The contribution a plan needs to exceed in order to win
+    target: i64,
+}
+
+impl Search<'_> {
+    /// Count the assignments of the remaining actions to the weights starting
+    /// at index `i` so that the total contribution exceeds the target
+    fn count(&self, i: usize, current: i64, inc: usize, dec: usize, maintain: usize) -> u64 {
+        let prefix = &self.prefix;
+        let end = self.weights.len();
+
+        // best case: increments on the largest, decrements on the smallest weights
+        let max = prefix[i + inc] - prefix[i] - (prefix[end] - prefix[end - dec]);
+        if current + max <= self.target {
+            return 0;
+        }
+
+        // worst case: decrements on the largest, increments on the smallest weights
+        let min = prefix[end] - prefix[end - inc] - (prefix[i + dec] - prefix[i]);
+        if current + min > self.target {
+            return multinomial(&[inc, dec, maintain]);
+        }
+
+        let w = self.weights[i];
+        let mut total = 0;
+        if inc > 0 {
+            total += self.count(i + 1, current + w, inc - 1, dec, maintain);
+        }
+        if dec > 0 {
+            total += self.count(i + 1, current - w, inc, dec - 1, maintain);
+        }
+        if maintain > 0 {
+            total += self.count(i + 1, current, inc, dec, maintain - 1);
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Action::*;
+
+    const TEMPLATE: [Action; 11] = [
+        Inc, Inc, Inc, Inc, Inc, Dec, Dec, Dec, Maintain, Maintain, Maintain,
+    ];
+
+    #[test]
+    fn count_winning_matches_simulation() {
+        let track = [Maintain, Inc, Maintain, Maintain, Dec, Maintain, Inc];
+        let rivals = [
+            vec![Inc, Dec, Maintain],
+            vec![Inc, Inc, Maintain, Dec],
+            TEMPLATE.to_vec(),
+        ];
+        for loops in [1, 3, 10] {
+            let model = RaceModel::new(&track, loops, &TEMPLATE);
+            assert!(model.exact);
+            for rival in &rivals {
+                assert_eq!(
+                    model.count_winning(rival),
+                    model.count_winning_brute_force(rival)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn essence_matches_simulation() {
+        let track = [Dec, Maintain, Maintain, Inc, Maintain];
+        let model = RaceModel::new(&track, 7, &TEMPLATE);
+        assert!(model.exact);
+        for plan in permutations_lexicographic(&TEMPLATE).take(200) {
+            assert_eq!(model.essence(&plan), race(&plan, &track, 7));
+        }
+    }
+}
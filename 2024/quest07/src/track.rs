@@ -0,0 +1,337 @@
+use std::fmt;
+
+use crate::{Action, DIRS};
+
+/// The direction in which a closed track should be followed
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    Clockwise,
+    CounterClockwise,
+}
+
+#[derive(Debug)]
+pub enum TrackError {
+    /// The track does not contain a start segment `S`
+    MissingStart,
+
+    /// The track contains more than one start segment
+    MultipleStarts { x: usize, y: usize },
+
+    /// A segment contains a symbol other than `+`, `-`, `=`, or `S`
+    UnknownSymbol { x: usize, y: usize, symbol: char },
+
+    /// There is no single loop through all segments, and the track has a
+    /// segment with more than two neighbors here
+    Branching { x: usize, y: usize },
+
+    /// A segment has less than two neighbors, so the track cannot continue
+    Broken { x: usize, y: usize },
+
+    /// A segment is not part of the loop starting at `S`
+    Disconnected { x: usize, y: usize },
+}
+
+impl fmt::Display for TrackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrackError::MissingStart => write!(f, "track has no start segment"),
+            TrackError::MultipleStarts { x, y } => {
+                write!(f, "additional start segment at ({x}, {y})")
+            }
+            TrackError::UnknownSymbol { x, y, symbol } => {
+                write!(f, "unknown symbol `{symbol}` at ({x}, {y})")
+            }
+            TrackError::Branching { x, y } => write!(f, "track branches at ({x}, {y})"),
+            TrackError::Broken { x, y } => write!(f, "track is broken at ({x}, {y})"),
+            TrackError::Disconnected { x, y } => {
+                write!(f, "segment at ({x}, {y}) is not connected to the start")
+            }
+        }
+    }
+}
+
+/// A closed track traced from its start segment
+pub struct Track {
+    grid: Vec<Vec<u8>>,
+
+    /// The positions of all segments in the order in which they are passed.
+    /// The first position is the segment after `S` and the last one is `S`
+    /// itself.
+    path: Vec<(usize, usize)>,
+}
+
+impl Track {
+    /// Parse a track consisting of a closed loop of `+`, `-`, and `=`
+    /// segments containing exactly one `S`, which may be located anywhere.
+    /// Parts of the track may run right next to each other, in which case
+    /// the loop through all segments is found by backtracking. The loop is
+    /// returned in the given direction.
+    pub fn parse(input: &str, direction: Direction) -> Result<Self, TrackError> {
+        let grid = input
+            .lines()
+            .map(|l| l.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+
+        let is_track = |x: i32, y: i32| {
+            x >= 0
+                && y >= 0
+                && (y as usize) < grid.len()
+                && (x as usize) < grid[y as usize].len()
+                && grid[y as usize][x as usize] != b' '
+        };
+
+        // find start and validate all segments
+        let mut start = None;
+        let mut segments = 0;
+        for (y, row) in grid.iter().enumerate() {
+            for (x, &c) in row.iter().enumerate() {
+                match c {
+                    b' ' => continue,
+                    b'+' | b'-' | b'=' => {}
+                    b'S' => {
+                        if start.is_some() {
+                            return Err(TrackError::MultipleStarts { x, y });
+                        }
+                        start = Some((x, y));
+                    }
+                    _ => {
+                        return Err(TrackError::UnknownSymbol {
+                            x,
+                            y,
+                            symbol: c as char,
+                        });
+                    }
+                }
+                segments += 1;
+            }
+        }
+        let Some(start) = start else {
+            return Err(TrackError::MissingStart);
+        };
+
+        let neighbors = |(x, y): (usize, usize)| {
+            DIRS.iter()
+                .map(move |(dx, dy)| (x as i32 + dx, y as i32 + dy))
+                .filter(|&(nx, ny)| is_track(nx, ny))
+                .map(|(nx, ny)| (nx as usize, ny as usize))
+        };
+        let cells = || {
+            grid.iter().enumerate().flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|&(_, &c)| c != b' ')
+                    .map(move |(x, _)| (x, y))
+            })
+        };
+
+        // every segment must be connected to the start
+        let mut visited = grid
+            .iter()
+            .map(|row| vec![false; row.len()])
+            .collect::<Vec<_>>();
+        visited[start.1][start.0] = true;
+        let mut stack = vec![start];
+        while let Some(pos) = stack.pop() {
+            for (x, y) in neighbors(pos) {
+                if !visited[y][x] {
+                    visited[y][x] = true;
+                    stack.push((x, y));
+                }
+            }
+        }
+        if let Some((x, y)) = cells().find(|&(x, y)| !visited[y][x]) {
+            return Err(TrackError::Disconnected { x, y });
+        }
+
+        // a segment on a loop needs a neighbor on either side
+        if let Some((x, y)) = cells().find(|&pos| neighbors(pos).count() < 2) {
+            return Err(TrackError::Broken { x, y });
+        }
+
+        // Search for a loop through all segments. Where parts of the track
+        // run next to each other, a segment has more than two neighbors, so
+        // we backtrack if a choice leaves a segment that can no longer be
+        // entered and left again.
+        for row in &mut visited {
+            row.fill(false);
+        }
+        visited[start.1][start.0] = true;
+        let mut path = vec![start];
+        let mut tried = vec![0];
+        // a loop alternates between segments with even and odd `x + y`
+        let even = cells().filter(|(x, y)| (x + y) % 2 == 0).count();
+        let balanced = 2 * even == segments;
+        let mut closed = false;
+        while balanced && let (Some(&pos), Some(i)) = (path.last(), tried.last_mut()) {
+            let Some(next) = neighbors(pos).nth(*i) else {
+                visited[pos.1][pos.0] = pos == start;
+                path.pop();
+                tried.pop();
+                continue;
+            };
+            *i += 1;
+            if next == start && path.len() == segments && segments > 2 {
+                closed = true;
+                break;
+            }
+            if visited[next.1][next.0] {
+                continue;
+            }
+            visited[next.1][next.0] = true;
+            let stranded = neighbors(pos).any(|n| {
+                !visited[n.1][n.0]
+                    && neighbors(n)
+                        .filter(|&m| m == next || m == start || !visited[m.1][m.0])
+                        .count()
+                        < 2
+            });
+            if stranded {
+                visited[next.1][next.0] = false;
+                continue;
+            }
+            path.push(next);
+            tried.push(0);
+        }
+        if !closed {
+            // without a loop, some segment must have a third neighbor
+            let (x, y) = cells()
+                .find(|&pos| neighbors(pos).count() > 2)
+                .unwrap_or(start);
+            return Err(TrackError::Branching { x, y });
+        }
+        path.rotate_left(1);
+
+        // The signed area of the loop is positive if it runs clockwise (with
+        // the y axis pointing down). Reverse it if necessary but keep `S` at
+        // the end.
+        let area = path
+            .iter()
+            .zip(path.iter().cycle().skip(1))
+            .map(|(a, b)| a.0 as i64 * b.1 as i64 - b.0 as i64 * a.1 as i64)
+            .sum::<i64>();
+        let traced = if area > 0 {
+            Direction::Clockwise
+        } else {
+            Direction::CounterClockwise
+        };
+        if traced != direction {
+            path.pop();
+            path.reverse();
+            path.push(start);
+        }
+
+        Ok(Track { grid, path })
+    }
+
+    /// Get the actions of all segments in the order in which they are passed
+    pub fn actions(&self) -> Vec<Action> {
+        self.path
+            .iter()
+            .map(|&(x, y)| match self.grid[y][x] {
+                b'+' => Action::Inc,
+                b'-' => Action::Dec,
+                _ => Action::Maintain,
+            })
+            .collect()
+    }
+
+    /// Render the track with each segment replaced by an arrow pointing in
+    /// the direction of travel
+    pub fn render(&self) -> String {
+        let mut grid = self.grid.clone();
+        for (i, &(x, y)) in self.path.iter().enumerate() {
+            let (nx, ny) = self.path[(i + 1) % self.path.len()];
+            if grid[y][x] != b'S' {
+                grid[y][x] = if ny == y {
+                    if nx > x { b'>' } else { b'<' }
+                } else if ny > y {
+                    b'v'
+                } else {
+                    b'^'
+                };
+            }
+        }
+        grid.iter()
+            .map(|row| String::from_utf8_lossy(row))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A track whose upper and lower parts run right next to each other
+    const PARALLEL: &str = "S-=+=\n+===-";
+
+    #[test]
+    fn parallel_runs() {
+        let track = Track::parse(PARALLEL, Direction::Clockwise).unwrap();
+        assert_eq!(
+            track.path,
+            [
+                (1, 0),
+                (2, 0),
+                (3, 0),
+                (4, 0),
+                (4, 1),
+                (3, 1),
+                (2, 1),
+                (1, 1),
+                (0, 1),
+                (0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn render() {
+        let track = Track::parse(PARALLEL, Direction::Clockwise).unwrap();
+        assert_eq!(track.render(), "S>>>v\n^<<<<");
+        let track = Track::parse(PARALLEL, Direction::CounterClockwise).unwrap();
+        assert_eq!(track.render(), "S<<<<\n>>>>^");
+    }
+
+    #[test]
+    fn parallel_loop() {
+        let track = Track::parse("=====\nS====", Direction::Clockwise).unwrap();
+        assert_eq!(track.path.len(), 10);
+        assert_eq!(track.path[..2], [(0, 0), (1, 0)]);
+        assert_eq!(track.path[9], (0, 1));
+    }
+
+    #[test]
+    fn directions() {
+        // following the track in the opposite direction must pass the same
+        // segments in reverse order (except for `S`, which always comes last)
+        let actions = Track::parse(PARALLEL, Direction::Clockwise)
+            .unwrap()
+            .actions();
+        let mut reversed = Track::parse(PARALLEL, Direction::CounterClockwise)
+            .unwrap()
+            .actions();
+        reversed[..actions.len() - 1].reverse();
+        assert_eq!(reversed, actions);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            Track::parse("S=+\n=  ", Direction::Clockwise),
+            Err(TrackError::Broken { x: 2, y: 0 })
+        ));
+        assert!(matches!(
+            Track::parse("S==\n= = +\n===", Direction::Clockwise),
+            Err(TrackError::Disconnected { x: 4, y: 1 })
+        ));
+        assert!(matches!(
+            Track::parse("S==\n===\n===", Direction::Clockwise),
+            Err(TrackError::Branching { x: 1, y: 0 })
+        ));
+        assert!(matches!(
+            Track::parse("==\n==", Direction::Clockwise),
+            Err(TrackError::MissingStart)
+        ));
+    }
+}
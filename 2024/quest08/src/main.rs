@@ -1,5 +1,8 @@
+use shrine::ShrineBuilder;
 use std::fs;
 
+mod shrine;
+
 fn part1() {
     let available_blocks = fs::read_to_string("everybody_codes_e2024_q08_p1.txt")
         .unwrap()
@@ -40,39 +43,25 @@ fn part2() {
     println!("{}", width * (required_blocks - available_blocks));
 }
 
-fn part3(available_blocks: u64) {
+fn part3(available_blocks: u128) {
     let priests = fs::read_to_string("everybody_codes_e2024_q08_p3.txt")
         .unwrap()
         .parse::<u64>()
         .unwrap();
 
-    let acolytes = 10;
-
-    let mut required_blocks = 1;
-    let mut width = 1;
-    let mut height = 1;
-    let mut heights = vec![1];
-    while required_blocks < available_blocks {
-        height = (height * priests) % acolytes + acolytes;
-        width += 2;
-        required_blocks += width * height;
-        heights.push(height);
-    }
+    let builder = ShrineBuilder::new(priests, 10, available_blocks);
+    let shrine = builder.build();
+    println!("{}", shrine.deficit());
 
-    let mut total_height = heights.iter().sum::<u64>();
-    let mut empty = 0;
-    for (i, h) in heights.iter().take(heights.len() - 1).enumerate() {
-        let e = (priests * width * total_height) % acolytes;
-        empty += if i == 0 { e } else { e * 2 };
-        total_height -= h;
+    let max_width = builder.max_width();
+    if cfg!(feature = "visualize") {
+        eprintln!("Maximum width with {available_blocks} blocks: {max_width}");
     }
-
-    println!("{}", required_blocks - empty - available_blocks);
 }
 
 fn main() {
     part1();
     part2();
-    part3(202400000u64); // new number of available blocks
-    part3(202400000000u64); // original number before the puzzle was updated
+    part3(202400000); // new number of available blocks
+    part3(202400000000); // original number before the puzzle was updated
 }
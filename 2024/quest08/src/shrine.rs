@@ -0,0 +1,162 @@
+/// Builds a hollow shrine from a given number of priests, acolytes, and
+/// available blocks. The thickness of each layer depends on the thickness of
+/// the previous one: `(previous * priests) % acolytes + acolytes`. Block
+/// counts are calculated with 128-bit integers so that even very large numbers
+/// of available blocks do not cause an overflow.
+pub struct ShrineBuilder {
+    priests: u64,
+    acolytes: u64,
+    available_blocks: u128,
+}
+
+/// A shrine consisting of a number of layers. The top layer has a width of 1
+/// and each subsequent layer is 2 blocks wider than the previous one.
+pub struct Shrine {
+    priests: u64,
+    acolytes: u64,
+    available_blocks: u128,
+
+    /// The thickness of each layer from top to bottom
+    layers: Vec<u64>,
+}
+
+impl ShrineBuilder {
+    pub fn new(priests: u64, acolytes: u64, available_blocks: u128) -> Self {
+        assert!(acolytes > 0, "There must be at least one acolyte");
+        Self {
+            priests,
+            acolytes,
+            available_blocks,
+        }
+    }
+
+    /// Calculate the thickness of the layer following a layer with the given
+    /// thickness
+    fn next_layer(&self, thickness: u64) -> u64 {
+        let t = (thickness as u128 * self.priests as u128) % self.acolytes as u128;
+        t as u64 + self.acolytes
+    }
+
+    /// Build the smallest shrine that uses up all available blocks, i.e. the
+    /// smallest shrine whose number of blocks (after removing blocks from the
+    /// inner columns) is at least the number of available blocks
+    pub fn build(&self) -> Shrine {
+        let mut shrine = Shrine {
+            priests: self.priests,
+            acolytes: self.acolytes,
+            available_blocks: self.available_blocks,
+            layers: vec![1],
+        };
+
+        // add layers until we have enough blocks without considering the
+        // blocks removed from the inner columns
+        let mut required_blocks = 1u128;
+        let mut thickness = 1;
+        while required_blocks < self.available_blocks {
+            thickness = self.next_layer(thickness);
+            shrine.layers.push(thickness);
+            required_blocks += shrine.width() as u128 * thickness as u128;
+        }
+
+        // Removing blocks might cause the shrine to become too small again.
+        // Since less than `acolytes` blocks are removed from each column and
+        // each new layer adds at least `acolytes` blocks per column, this
+        // loop terminates after at most a few iterations.
+        while shrine.blocks() < self.available_blocks {
+            thickness = self.next_layer(thickness);
+            shrine.layers.push(thickness);
+        }
+
+        shrine
+    }
+
+    /// Find the width of the largest shrine that can be built with the
+    /// available blocks without running out of blocks
+    pub fn max_width(&self) -> u64 {
+        let shrine = self.build();
+        if shrine.blocks() == self.available_blocks {
+            shrine.width()
+        } else {
+            shrine.width().saturating_sub(2)
+        }
+    }
+}
+
+impl Shrine {
+    /// The width of the bottom layer
+    pub fn width(&self) -> u64 {
+        self.layers.len() as u64 * 2 - 1
+    }
+
+    /// Get the height of each column from the center column outwards. Since
+    /// the shrine is symmetric, the heights of the columns on the left and
+    /// the right side are the same.
+    pub fn column_heights(&self) -> Vec<u128> {
+        let mut heights = vec![0u128; self.layers.len()];
+        let mut total = 0u128;
+        for (i, &t) in self.layers.iter().enumerate().rev() {
+            total += t as u128;
+            heights[i] = total;
+        }
+        heights
+    }
+
+    /// Get the number of blocks removed from each column from the center
+    /// column outwards. No blocks are removed from the outermost columns.
+    pub fn removed_blocks(&self) -> Vec<u128> {
+        let width = self.width() as u128;
+        let priests = self.priests as u128;
+        let acolytes = self.acolytes as u128;
+        let mut heights = self.column_heights();
+        let outermost = heights.len() - 1;
+        for (i, h) in heights.iter_mut().enumerate() {
+            *h = if i == outermost {
+                0
+            } else {
+                // reduce each factor first to avoid overflows
+                (priests % acolytes) * (width % acolytes) % acolytes * (*h % acolytes) % acolytes
+            };
+        }
+        heights
+    }
+
+    /// Get the total number of blocks after removing blocks from the inner
+    /// columns
+    pub fn blocks(&self) -> u128 {
+        let heights = self.column_heights();
+        let removed = self.removed_blocks();
+        heights
+            .iter()
+            .zip(removed)
+            .enumerate()
+            .map(|(i, (h, r))| if i == 0 { h - r } else { (h - r) * 2 })
+            .sum()
+    }
+
+    /// Get the number of blocks missing to build this shrine with the
+    /// available blocks
+    pub fn deficit(&self) -> u128 {
+        self.blocks().saturating_sub(self.available_blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let builder = ShrineBuilder::new(2, 5, 160);
+        let shrine = builder.build();
+        assert_eq!(shrine.deficit(), 2);
+        assert_eq!(builder.max_width(), shrine.width() - 2);
+    }
+
+    #[test]
+    fn max_width_with_exact_blocks() {
+        let shrine = ShrineBuilder::new(2, 5, 160).build();
+        let builder = ShrineBuilder::new(2, 5, shrine.blocks());
+        assert_eq!(builder.build().deficit(), 0);
+        assert_eq!(builder.max_width(), shrine.width());
+    }
+}
@@ -0,0 +1,500 @@
+use std::fmt;
+
+/// The width and height of a block including its frame
+pub const BLOCK_SIZE: usize = 8;
+
+/// The offsets of the runes in a block's frame
+const FRAME: [usize; 4] = [0, 1, 6, 7];
+
+/// The offsets of the cells inside a block
+const INNER: [usize; 4] = [2, 3, 4, 5];
+
+/// A runic word read from the inner cells of a solved block
+pub struct Word(Vec<u8>);
+
+impl Word {
+    /// Calculate the word's power: the sum of each rune's base power (its
+    /// position in the alphabet) multiplied by its position in the word
+    pub fn power(&self) -> usize {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (i + 1) * (c - b'A' + 1) as usize)
+            .sum()
+    }
+}
+
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", String::from_utf8_lossy(&self.0))
+    }
+}
+
+/// The reason why a block could not be solved. Coordinates are relative to
+/// the block's top-left corner.
+#[derive(Debug)]
+pub enum Unsolvable {
+    /// No rune fits into the given cell
+    Contradiction { x: usize, y: usize },
+
+    /// More than one rune fits into the given cell
+    Ambiguous { x: usize, y: usize },
+
+    /// The runes are known, but it is unclear which of the question marks in
+    /// the given frame row or column they belong to
+    AmbiguousFrame { x: usize, y: usize },
+}
+
+impl fmt::Display for Unsolvable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unsolvable::Contradiction { x, y } => write!(f, "no rune fits into ({x}, {y})"),
+            Unsolvable::Ambiguous { x, y } => write!(f, "more than one rune fits into ({x}, {y})"),
+            Unsolvable::AmbiguousFrame { x, y } => {
+                write!(f, "question marks around ({x}, {y}) cannot be told apart")
+            }
+        }
+    }
+}
+
+/// A candidate for an inner cell
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Candidate {
+    /// A known rune from the frame
+    Rune(u8),
+
+    /// The cell's row and column both have an unused question mark, so any
+    /// rune would fit
+    Any,
+}
+
+/// The inner cells of a solved block and the runes that replace the
+/// question marks in each frame row and column
+struct Solution {
+    cells: [[u8; 4]; 4],
+    row_fills: [Vec<u8>; 4],
+    col_fills: [Vec<u8>; 4],
+}
+
+/// The state of the backtracking search for a single block
+struct Search {
+    /// The inner cells (0 if the cell is empty)
+    cells: [[u8; 4]; 4],
+
+    /// Runes from each frame row that have not been used in the inner cells
+    /// yet, and the number of unused question marks in the row
+    rows: [(Vec<u8>, usize); 4],
+
+    /// Same as `rows` but for frame columns
+    cols: [(Vec<u8>, usize); 4],
+
+    /// Runes that question marks have been replaced with in each row and
+    /// column
+    row_fills: [Vec<u8>; 4],
+    col_fills: [Vec<u8>; 4],
+
+    /// The first solution found
+    solution: Option<Solution>,
+
+    /// The number of solutions found so far (we stop at 2)
+    solutions: usize,
+
+    /// The first cell for which no candidate could be found
+    contradiction: Option<Unsolvable>,
+
+    /// The first cell for which more than one candidate led to a solution
+    ambiguity: Option<Unsolvable>,
+}
+
+impl Search {
+    /// Get all candidates for the inner cell at (x, y)
+    fn candidates(&self, x: usize, y: usize) -> Vec<Candidate> {
+        let (row, row_wild) = &self.rows[y];
+        let (col, col_wild) = &self.cols[x];
+
+        // runes must not appear twice in the same row or column
+        let unused = |r: &u8| (0..4).all(|i| self.cells[y][i] != *r && self.cells[i][x] != *r);
+
+        let mut result = Vec::new();
+        for r in row.iter().filter(|r| unused(r)) {
+            if col.contains(r) || *col_wild > 0 {
+                result.push(Candidate::Rune(*r));
+            }
+        }
+        if *row_wild > 0 {
+            for c in col.iter().filter(|c| unused(c)) {
+                if !row.contains(c) {
+                    result.push(Candidate::Rune(*c));
+                }
+            }
+            if *col_wild > 0 {
+                result.push(Candidate::Any);
+            }
+        }
+        result
+    }
+
+    /// Take a rune from the given frame row or column. Use a question mark if
+    /// the rune is not there. Returns `true` if a question mark was used.
+    fn take(line: &mut (Vec<u8>, usize), fills: &mut Vec<u8>, rune: u8) -> bool {
+        if let Some(i) = line.0.iter().position(|&r| r == rune) {
+            line.0.swap_remove(i);
+            false
+        } else {
+            line.1 -= 1;
+            fills.push(rune);
+            true
+        }
+    }
+
+    /// Put a rune back into the given frame row or column
+    fn put_back(line: &mut (Vec<u8>, usize), fills: &mut Vec<u8>, rune: u8, wild: bool) {
+        if wild {
+            fills.pop();
+            line.1 += 1;
+        } else {
+            line.0.push(rune);
+        }
+    }
+
+    fn solve(&mut self) {
+        // find the empty cell with the fewest candidates
+        let mut best: Option<(usize, usize, Vec<Candidate>)> = None;
+        for y in 0..4 {
+            for x in 0..4 {
+                if self.cells[y][x] != 0 {
+                    continue;
+                }
+                let c = self.candidates(x, y);
+                if best.as_ref().is_none_or(|b| c.len() < b.2.len()) {
+                    best = Some((x, y, c));
+                }
+            }
+        }
+
+        let Some((x, y, candidates)) = best else {
+            // all cells are filled
+            self.solutions += 1;
+            if self.solution.is_none() {
+                self.solution = Some(Solution {
+                    cells: self.cells,
+                    row_fills: self.row_fills.clone(),
+                    col_fills: self.col_fills.clone(),
+                });
+            }
+            return;
+        };
+
+        let here = (INNER[x], INNER[y]);
+        if candidates.is_empty() && self.contradiction.is_none() {
+            self.contradiction = Some(Unsolvable::Contradiction {
+                x: here.0,
+                y: here.1,
+            });
+        }
+
+        let mut branches_with_solutions = 0;
+        for c in candidates {
+            let before = self.solutions;
+            match c {
+                Candidate::Rune(rune) => {
+                    let row_wild = Self::take(&mut self.rows[y], &mut self.row_fills[y], rune);
+                    let col_wild = Self::take(&mut self.cols[x], &mut self.col_fills[x], rune);
+                    self.cells[y][x] = rune;
+                    self.solve();
+                    self.cells[y][x] = 0;
+                    Self::put_back(&mut self.cols[x], &mut self.col_fills[x], rune, col_wild);
+                    Self::put_back(&mut self.rows[y], &mut self.row_fills[y], rune, row_wild);
+                }
+
+                Candidate::Any => {
+                    // We cannot tell which rune belongs here. If the rest of
+                    // the block can be solved, the block is ambiguous.
+                    self.rows[y].1 -= 1;
+                    self.cols[x].1 -= 1;
+                    self.cells[y][x] = b'?';
+                    self.solve();
+                    self.cells[y][x] = 0;
+                    self.rows[y].1 += 1;
+                    self.cols[x].1 += 1;
+                    if self.solutions > before {
+                        self.solutions = 2;
+                        if self.ambiguity.is_none() {
+                            self.ambiguity = Some(Unsolvable::Ambiguous {
+                                x: here.0,
+                                y: here.1,
+                            });
+                        }
+                    }
+                }
+            }
+
+            if self.solutions > before {
+                branches_with_solutions += 1;
+            }
+            if branches_with_solutions > 1 && self.ambiguity.is_none() {
+                self.ambiguity = Some(Unsolvable::Ambiguous {
+                    x: here.0,
+                    y: here.1,
+                });
+            }
+            if self.solutions > 1 {
+                return;
+            }
+        }
+    }
+}
+
+/// A wall consisting of runic blocks. Blocks may overlap and share their
+/// frames.
+pub struct Wall {
+    grid: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+impl Wall {
+    pub fn parse(input: &str) -> Self {
+        let lines = input.lines().collect::<Vec<_>>();
+        let width = lines[0].len();
+        let height = lines.len();
+        let grid = lines
+            .into_iter()
+            .flat_map(|l| l.as_bytes().iter().copied())
+            .collect::<Vec<_>>();
+        Self {
+            grid,
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn get(&self, sx: usize, sy: usize, x: usize, y: usize) -> u8 {
+        self.grid[(sy + y) * self.width + sx + x]
+    }
+
+    fn set(&mut self, sx: usize, sy: usize, x: usize, y: usize, c: u8) {
+        self.grid[(sy + y) * self.width + sx + x] = c;
+    }
+
+    /// Solve the block whose top-left corner is at (sx, sy). Question marks
+    /// in the frame are replaced by the runes that have been deduced for
+    /// them. The wall is only modified if the block has exactly one
+    /// solution.
+    pub fn solve_block(&mut self, sx: usize, sy: usize) -> Result<Word, Unsolvable> {
+        let line = |get: &dyn Fn(usize) -> u8| {
+            let mut runes = Vec::new();
+            let mut wild = 0;
+            for i in FRAME {
+                match get(i) {
+                    b'?' => wild += 1,
+                    c => runes.push(c),
+                }
+            }
+            (runes, wild)
+        };
+
+        let mut search = Search {
+            cells: [[0; 4]; 4],
+            rows: std::array::from_fn(|y| line(&|i| self.get(sx, sy, i, INNER[y]))),
+            cols: std::array::from_fn(|x| line(&|i| self.get(sx, sy, INNER[x], i))),
+            row_fills: Default::default(),
+            col_fills: Default::default(),
+            solution: None,
+            solutions: 0,
+            contradiction: None,
+            ambiguity: None,
+        };
+
+        // take runes of cells that have already been filled
+        for (y, &iy) in INNER.iter().enumerate() {
+            for (x, &ix) in INNER.iter().enumerate() {
+                let c = self.get(sx, sy, ix, iy);
+                if c == b'.' {
+                    continue;
+                }
+                let (row, col) = (&search.rows[y], &search.cols[x]);
+                if (!row.0.contains(&c) && row.1 == 0) || (!col.0.contains(&c) && col.1 == 0) {
+                    return Err(Unsolvable::Contradiction { x: ix, y: iy });
+                }
+                Search::take(&mut search.rows[y], &mut search.row_fills[y], c);
+                Search::take(&mut search.cols[x], &mut search.col_fills[x], c);
+                search.cells[y][x] = c;
+            }
+        }
+
+        search.solve();
+
+        if search.solutions == 0 {
+            return Err(search.contradiction.unwrap());
+        } else if search.solutions > 1 {
+            return Err(search.ambiguity.unwrap());
+        }
+        let Solution {
+            cells,
+            row_fills,
+            col_fills,
+        } = search.solution.unwrap();
+
+        // make sure we know where the deduced runes belong in the frame
+        for (i, &inner) in INNER.iter().enumerate() {
+            if row_fills[i].len() > 1 {
+                return Err(Unsolvable::AmbiguousFrame { x: 0, y: inner });
+            }
+            if col_fills[i].len() > 1 {
+                return Err(Unsolvable::AmbiguousFrame { x: inner, y: 0 });
+            }
+        }
+
+        // write solution back into the wall
+        for (i, &inner) in INNER.iter().enumerate() {
+            for f in FRAME {
+                if let Some(&r) = row_fills[i].first()
+                    && self.get(sx, sy, f, inner) == b'?'
+                {
+                    self.set(sx, sy, f, inner, r);
+                }
+                if let Some(&c) = col_fills[i].first()
+                    && self.get(sx, sy, inner, f) == b'?'
+                {
+                    self.set(sx, sy, inner, f, c);
+                }
+            }
+        }
+        let mut word = Vec::new();
+        for (y, &iy) in INNER.iter().enumerate() {
+            for (x, &ix) in INNER.iter().enumerate() {
+                self.set(sx, sy, ix, iy, cells[y][x]);
+                word.push(cells[y][x]);
+            }
+        }
+
+        Ok(Word(word))
+    }
+
+    /// Solve all given blocks. Since blocks may share question marks in their
+    /// frames, solving one block can make another one solvable. Blocks are
+    /// therefore tried repeatedly until no more progress can be made.
+    /// Returns the result for each block in the order in which the blocks
+    /// were given.
+    pub fn solve_all(&mut self, blocks: &[(usize, usize)]) -> Vec<Result<Word, Unsolvable>> {
+        let mut results = blocks
+            .iter()
+            .map(|_| Err(Unsolvable::Contradiction { x: 0, y: 0 }))
+            .collect::<Vec<_>>();
+        let mut remaining = (0..blocks.len()).collect::<Vec<_>>();
+        loop {
+            let mut still_remaining = Vec::new();
+            for &i in &remaining {
+                let (sx, sy) = blocks[i];
+                results[i] = self.solve_block(sx, sy);
+                if results[i].is_err() {
+                    still_remaining.push(i);
+                }
+            }
+            if still_remaining.len() == remaining.len() {
+                break;
+            }
+            remaining = still_remaining;
+        }
+        results
+    }
+}
+
+impl fmt::Display for Wall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.grid.chunks(self.width) {
+            writeln!(f, "{}", String::from_utf8_lossy(row))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(block: &str) -> Result<String, Unsolvable> {
+        Wall::parse(block).solve_block(0, 0).map(|w| w.to_string())
+    }
+
+    #[test]
+    fn solvable() {
+        let word = solve(
+            "**PCBS**
+**RLNW**
+BV....PT
+CR....HZ
+FL....JW
+SG....MN
+**FTZV**
+**GMJH**",
+        )
+        .unwrap();
+        assert_eq!(word, "PTBVRCZHFLJWGMNS");
+        assert_eq!(Word(word.into_bytes()).power(), 1851);
+    }
+
+    #[test]
+    fn needs_backtracking() {
+        // every rune of the frame fits into several cells, and some of the
+        // choices only fail later
+        let word =
+            solve("**BDAE**\n**AHCF**\nFE....BG\nBD....CH\nHC....FA\nDA....EB\n**HBDC**\n**GFEB**");
+        assert_eq!(word.unwrap(), "GFEBHBDCAHCFBDAE");
+    }
+
+    #[test]
+    fn unsolvable() {
+        let result =
+            solve("**ABCD**\n**EFGH**\nIJ....KL\nMN....OP\nQR....ST\nUV....WX\n**YZAB**\n**CDEF**");
+        assert!(matches!(result, Err(Unsolvable::Contradiction { .. })));
+
+        // any Latin square fits
+        let result =
+            solve("**ABCD**\n**BCDA**\nAB....CD\nBC....DA\nCD....AB\nDA....BC\n**CDAB**\n**DABC**");
+        assert!(matches!(result, Err(Unsolvable::Ambiguous { .. })));
+    }
+
+    #[test]
+    fn shared_question_marks() {
+        let mut wall = Wall::parse(
+            "**XFZB**DCST**
+**LWQK**GQJH**
+?G....WL....DQ
+BS....H?....CN
+P?....KJ....TV
+NM....Z?....SG
+**NSHM**VKWZ**
+**PJGV**XFNL**
+WQ....?L....YS
+FX....DJ....HV
+?Y....WM....?J
+TJ....YK....LP
+**XRTK**BMSP**
+**DWZN**GCJV**",
+        );
+        let blocks = [(0, 0), (6, 0), (0, 6), (6, 6)];
+        let results = wall.solve_all(&blocks);
+        assert!(results[..2].iter().all(|r| r.is_ok()));
+        assert!(
+            results[2..]
+                .iter()
+                .all(|r| matches!(r, Err(Unsolvable::Contradiction { .. })))
+        );
+        let total = results
+            .into_iter()
+            .flatten()
+            .map(|w| w.power())
+            .sum::<usize>();
+        assert_eq!(total, 3889);
+    }
+}
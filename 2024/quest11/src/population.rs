@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+use num::{BigUint, ToPrimitive};
+
+/// A number of termites. Counts are stored as `u64` as long as possible and
+/// automatically switch to arbitrary precision if they overflow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Count {
+    Small(u64),
+
+    /// Always larger than `u64::MAX`
+    Big(BigUint),
+}
+
+impl Count {
+    fn from_big(b: BigUint) -> Self {
+        match b.to_u64() {
+            Some(s) => Count::Small(s),
+            None => Count::Big(b),
+        }
+    }
+
+    fn to_big(&self) -> BigUint {
+        match self {
+            Count::Small(s) => BigUint::from(*s),
+            Count::Big(b) => b.clone(),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        matches!(self, Count::Small(0))
+    }
+}
+
+impl Add for &Count {
+    type Output = Count;
+
+    fn add(self, rhs: &Count) -> Count {
+        match (self, rhs) {
+            (Count::Small(a), Count::Small(b)) => match a.checked_add(*b) {
+                Some(s) => Count::Small(s),
+                None => Count::Big(BigUint::from(*a) + b),
+            },
+            _ => Count::Big(self.to_big() + rhs.to_big()),
+        }
+    }
+}
+
+impl Mul for &Count {
+    type Output = Count;
+
+    fn mul(self, rhs: &Count) -> Count {
+        match (self, rhs) {
+            (Count::Small(a), Count::Small(b)) => match a.checked_mul(*b) {
+                Some(s) => Count::Small(s),
+                None => Count::Big(BigUint::from(*a) * b),
+            },
+            _ if self.is_zero() || rhs.is_zero() => Count::Small(0),
+            _ => Count::Big(self.to_big() * rhs.to_big()),
+        }
+    }
+}
+
+impl Sub for &Count {
+    type Output = Count;
+
+    /// Subtract two counts. Panics if the result would be negative.
+    fn sub(self, rhs: &Count) -> Count {
+        match (self, rhs) {
+            (Count::Small(a), Count::Small(b)) => Count::Small(a - b),
+            _ => Count::from_big(self.to_big() - rhs.to_big()),
+        }
+    }
+}
+
+impl PartialOrd for Count {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Count {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self, other) {
+            (Count::Small(a), Count::Small(b)) => a.cmp(b),
+            (Count::Small(_), Count::Big(_)) => std::cmp::Ordering::Less,
+            (Count::Big(_), Count::Small(_)) => std::cmp::Ordering::Greater,
+            (Count::Big(a), Count::Big(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl fmt::Display for Count {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Count::Small(s) => write!(f, "{s}"),
+            Count::Big(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+/// A square matrix of counts
+#[derive(Clone)]
+struct Matrix(Vec<Vec<Count>>);
+
+impl Matrix {
+    fn identity(n: usize) -> Self {
+        let mut m = vec![vec![Count::Small(0); n]; n];
+        for (i, row) in m.iter_mut().enumerate() {
+            row[i] = Count::Small(1);
+        }
+        Matrix(m)
+    }
+
+    fn mul(&self, other: &Matrix) -> Matrix {
+        let n = self.0.len();
+        let mut result = vec![vec![Count::Small(0); n]; n];
+        for (i, row) in result.iter_mut().enumerate() {
+            for k in 0..n {
+                let a = &self.0[i][k];
+                if a.is_zero() {
+                    continue;
+                }
+                for (j, r) in row.iter_mut().enumerate() {
+                    let b = &other.0[k][j];
+                    if !b.is_zero() {
+                        *r = &*r + &(a * b);
+                    }
+                }
+            }
+        }
+        Matrix(result)
+    }
+
+    /// Raise the matrix to the given power by repeated squaring
+    fn pow(&self, mut exp: u64) -> Matrix {
+        let mut result = Matrix::identity(self.0.len());
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(&base);
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.mul(&base);
+            }
+        }
+        result
+    }
+}
+
+/// Rules describing which categories of termites each category turns into
+/// after one day
+pub struct Rules<'a> {
+    names: Vec<&'a str>,
+    index: HashMap<&'a str, usize>,
+
+    /// `transitions.0[i][j]` is the number of termites of category `j` a
+    /// termite of category `i` turns into after one day
+    transitions: Matrix,
+}
+
+impl<'a> Rules<'a> {
+    /// Parse rules of the form `A:B,C`. Categories that only appear on the
+    /// right-hand side of a rule die out after one day.
+    pub fn parse(input: &'a str) -> Self {
+        let mut names = Vec::new();
+        let mut index = HashMap::new();
+        let mut edges = Vec::new();
+
+        let mut get_or_insert = |name: &'a str| {
+            *index.entry(name).or_insert_with(|| {
+                names.push(name);
+                names.len() - 1
+            })
+        };
+
+        for l in input.lines() {
+            let (from, to) = l.split_once(':').unwrap();
+            let from = get_or_insert(from);
+            for t in to.split(',') {
+                edges.push((from, get_or_insert(t)));
+            }
+        }
+
+        let mut transitions = Matrix(vec![vec![Count::Small(0); names.len()]; names.len()]);
+        for (from, to) in edges {
+            let c = &mut transitions.0[from][to];
+            *c = &*c + &Count::Small(1);
+        }
+
+        Self {
+            names,
+            index,
+            transitions,
+        }
+    }
+
+    /// Get the names of all categories that have a rule
+    pub fn categories(&self) -> impl Iterator<Item = &'a str> {
+        self.names
+            .iter()
+            .zip(&self.transitions.0)
+            .filter(|(_, row)| row.iter().any(|c| !c.is_zero()))
+            .map(|(n, _)| *n)
+    }
+
+    /// Project the population for all starting categories. For each
+    /// category, returns a histogram containing the number of termites of
+    /// each category after the given number of days, starting with a single
+    /// termite.
+    pub fn project_all(&self, days: u64) -> HashMap<&'a str, Vec<(&'a str, Count)>> {
+        let m = self.transitions.pow(days);
+        self.names
+            .iter()
+            .zip(m.0)
+            .map(|(&start, row)| (start, self.names.iter().copied().zip(row).collect()))
+            .collect()
+    }
+
+    /// Project the population starting with a single termite of the given
+    /// category. Returns the number of termites of each category after the
+    /// given number of days.
+    pub fn project(&self, start: &str, days: u64) -> Vec<(&'a str, Count)> {
+        let m = self.transitions.pow(days);
+        let i = self.index[start];
+        self.names
+            .iter()
+            .copied()
+            .zip(m.0[i].iter().cloned())
+            .collect()
+    }
+}
+
+/// Calculate the total number of termites in a histogram
+pub fn total(histogram: &[(&str, Count)]) -> Count {
+    histogram
+        .iter()
+        .fold(Count::Small(0), |acc, (_, c)| &acc + c)
+}
@@ -0,0 +1,296 @@
+use std::collections::HashSet;
+
+/// A target that has to be hit `hit_points` times to be destroyed
+#[derive(Clone, Copy, Debug)]
+pub struct Target {
+    pub x: i32,
+    pub y: i32,
+    pub hit_points: i32,
+}
+
+/// A projectile fired from a catapult segment with a given power after
+/// waiting for `delay` time steps
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shot {
+    pub segment: usize,
+    pub power: i32,
+    pub delay: i32,
+}
+
+impl Shot {
+    /// The shot's ranking value: the segment's number multiplied by the power
+    pub fn ranking(&self) -> i32 {
+        (self.segment as i32 + 1) * self.power
+    }
+}
+
+/// A collision of a shot with a target at position (x, y) and the given time
+#[derive(Clone, Copy, Debug)]
+pub struct Hit {
+    pub shot: Shot,
+    pub time: i32,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// The result of planning shots for a set of static targets
+pub struct Plan {
+    /// Each shot in the order in which it should be fired, together with the
+    /// index of the target it hits
+    pub shots: Vec<(usize, Shot)>,
+
+    /// Indexes of targets that could not be hit without hitting another
+    /// target first
+    pub blocked: Vec<usize>,
+}
+
+/// A catapult consisting of segments at x = 0 and arbitrary heights.
+/// Projectiles fly diagonally upwards for `power` time steps, then
+/// horizontally for another `power` steps, and finally diagonally downwards.
+pub struct Catapult {
+    segments: Vec<i32>,
+}
+
+impl Catapult {
+    pub fn new(segments: Vec<i32>) -> Self {
+        Self { segments }
+    }
+
+    /// Parse a map containing catapult segments labeled `A`, `B`, `C`, etc.,
+    /// and targets labeled `T` (one hit point) or `H` (two hit points). The
+    /// first column and the last row (the ground) are ignored. Coordinates
+    /// are relative to the bottom left corner.
+    pub fn parse_map(input: &str) -> (Self, Vec<Target>) {
+        let mut segments = Vec::new();
+        let mut targets = Vec::new();
+        for (y, l) in input.lines().rev().skip(1).enumerate() {
+            for (x, c) in l.chars().skip(1).enumerate() {
+                let (x, y) = (x as i32, y as i32);
+                match c {
+                    'T' => targets.push(Target {
+                        x,
+                        y,
+                        hit_points: 1,
+                    }),
+                    'H' => targets.push(Target {
+                        x,
+                        y,
+                        hit_points: 2,
+                    }),
+                    'A'..='Z' => {
+                        assert_eq!(x, 0, "Catapult segments must be in the first column");
+                        let i = (c as u8 - b'A') as usize;
+                        if segments.len() <= i {
+                            segments.resize(i + 1, 0);
+                        }
+                        segments[i] = y;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        (Self::new(segments), targets)
+    }
+
+    /// Calculate the position of a projectile fired from the given segment
+    /// with the given power after it has been flying for the given time
+    pub fn position(&self, segment: usize, power: i32, time: i32) -> (i32, i32) {
+        let y = self.segments[segment];
+        let up = time.min(power);
+        let down = (time - 2 * power).max(0);
+        (time, y + up - down)
+    }
+
+    /// Get all powers with which a projectile fired from the given segment
+    /// is at height `y` after it has been flying for `time` steps. If the
+    /// projectile is still flying upwards, only the smallest power is
+    /// returned.
+    fn powers(&self, segment: usize, time: i32, y: i32) -> Vec<i32> {
+        let dy = y - self.segments[segment];
+        let mut result = Vec::new();
+
+        // upwards: time <= power
+        if dy == time {
+            result.push(time);
+        }
+
+        // horizontal: power < time <= 2 * power
+        if dy > 0 && dy < time && time <= 2 * dy {
+            result.push(dy);
+        }
+
+        // downwards: 2 * power < time, y = power * 3 - time
+        if (dy + time) % 3 == 0 {
+            let p = (dy + time) / 3;
+            if p > 0 && 2 * p < time {
+                result.push(p);
+            }
+        }
+
+        result
+    }
+
+    /// Get all shots that hit a static target at (x, y)
+    pub fn aim(&self, x: i32, y: i32) -> Vec<Shot> {
+        let mut result = Vec::new();
+        for segment in 0..self.segments.len() {
+            for power in self.powers(segment, x, y) {
+                result.push(Shot {
+                    segment,
+                    power,
+                    delay: 0,
+                });
+            }
+        }
+        result
+    }
+
+    /// Check if the shot can reach the position it has after `time` steps
+    /// without passing through any of the given obstacles
+    fn is_clear(&self, shot: &Shot, time: i32, obstacles: &HashSet<(i32, i32)>) -> bool {
+        (1..time).all(|t| {
+            let p = self.position(shot.segment, shot.power, t);
+            !obstacles.contains(&p)
+        })
+    }
+
+    /// Plan shots destroying all given static targets. Targets are only shot
+    /// if the projectile does not hit another target before. For each target,
+    /// the shot with the lowest ranking value is chosen.
+    pub fn plan(&self, targets: &[Target]) -> Plan {
+        let mut remaining = targets.iter().map(|t| (t.x, t.y)).collect::<HashSet<_>>();
+        let mut open = (0..targets.len()).collect::<Vec<_>>();
+        let mut shots = Vec::new();
+
+        loop {
+            let mut still_open = Vec::new();
+            for &i in &open {
+                let t = &targets[i];
+                let best = self
+                    .aim(t.x, t.y)
+                    .into_iter()
+                    .filter(|s| self.is_clear(s, t.x, &remaining))
+                    .min_by_key(|s| s.ranking());
+                let Some(shot) = best else {
+                    still_open.push(i);
+                    continue;
+                };
+                for _ in 0..t.hit_points {
+                    shots.push((i, shot));
+                }
+                remaining.remove(&(t.x, t.y));
+            }
+            if still_open.len() == open.len() {
+                break;
+            }
+            open = still_open;
+        }
+
+        Plan {
+            shots,
+            blocked: open,
+        }
+    }
+
+    /// Get all hits on a meteor that starts at (x, y) and moves diagonally
+    /// down to the left by one step per time unit. Projectiles can be fired
+    /// with a delay. Only hits at or above the ground are returned.
+    pub fn hits(&self, x: i32, y: i32) -> Vec<Hit> {
+        let mut result = Vec::new();
+        for segment in 0..self.segments.len() {
+            // The projectile and the meteor approach each other by 2 steps
+            // per time unit, so the delay must have the same parity as x.
+            let mut delay = x % 2;
+            loop {
+                let time = (x + delay) / 2;
+                let flight = time - delay;
+                let hy = y - time;
+                if flight < 1 || hy < 0 {
+                    break;
+                }
+                for power in self.powers(segment, flight, hy) {
+                    result.push(Hit {
+                        shot: Shot {
+                            segment,
+                            power,
+                            delay,
+                        },
+                        time,
+                        x: flight,
+                        y: hy,
+                    });
+                }
+                delay += 2;
+            }
+        }
+        result
+    }
+
+    /// Find the best hit on a meteor starting at (x, y): the one at the
+    /// highest altitude and, among those, the one with the lowest ranking
+    /// value
+    pub fn intercept(&self, x: i32, y: i32) -> Option<Hit> {
+        self.hits(x, y)
+            .into_iter()
+            .min_by_key(|h| (-h.y, h.shot.ranking()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(input: &str) -> i32 {
+        let (catapult, targets) = Catapult::parse_map(input);
+        let plan = catapult.plan(&targets);
+        assert!(plan.blocked.is_empty());
+        plan.shots.iter().map(|(_, s)| s.ranking()).sum()
+    }
+
+    #[test]
+    fn static_targets() {
+        let map = ".............\n.C...........\n.B......T....\n.A......T.T..\n=============";
+        assert_eq!(total(map), 13);
+        let map = ".............\n.C...........\n.B......H....\n.A......T.H..\n=============";
+        assert_eq!(total(map), 22);
+    }
+
+    #[test]
+    fn blocked_targets() {
+        let catapult = Catapult::new(vec![0]);
+        let targets = [(2, 2), (1, 1), (1, 5)].map(|(x, y)| Target {
+            x,
+            y,
+            hit_points: 1,
+        });
+        let plan = catapult.plan(&targets);
+
+        // the target at (2, 2) can only be hit once the one at (1, 1) is
+        // destroyed, and the one at (1, 5) cannot be reached at all
+        assert_eq!(
+            plan.shots.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            [1, 0]
+        );
+        assert_eq!(plan.blocked, [2]);
+    }
+
+    #[test]
+    fn meteors() {
+        let catapult = Catapult::new(vec![0, 1, 2]);
+        let rankings = [(6, 5), (6, 7), (10, 5)]
+            .map(|(x, y)| catapult.intercept(x, y).unwrap().shot.ranking());
+        assert_eq!(rankings.iter().sum::<i32>(), 11);
+
+        // every hit must be where both the projectile and the meteor are
+        for x in 1..40 {
+            for y in 0..40 {
+                for hit in catapult.hits(x, y) {
+                    let flight = hit.time - hit.shot.delay;
+                    let shot = catapult.position(hit.shot.segment, hit.shot.power, flight);
+                    assert_eq!(shot, (hit.x, hit.y));
+                    assert_eq!((x - hit.time, y - hit.time), (hit.x, hit.y));
+                }
+            }
+        }
+    }
+}
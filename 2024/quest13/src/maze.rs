@@ -0,0 +1,280 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+pub const DIRS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+/// Describes how much moving through the maze costs. Levels are cyclic:
+/// after the highest level comes level 0 again.
+pub struct CostModel {
+    /// The number of levels
+    pub levels: usize,
+
+    /// The cost of moving to a neighboring cell
+    pub step: usize,
+
+    /// The cost of changing the level by one
+    pub level_change: usize,
+}
+
+impl CostModel {
+    /// Calculate the shortest signed level change from level `a` to level
+    /// `b`, taking wraparound into account
+    pub fn level_delta(&self, a: usize, b: usize) -> i32 {
+        let up = (b + self.levels - a) % self.levels;
+        let down = (a + self.levels - b) % self.levels;
+        if up <= down {
+            up as i32
+        } else {
+            -(down as i32)
+        }
+    }
+
+    /// Calculate the cost of moving from a cell at level `a` to a
+    /// neighboring cell at level `b`
+    pub fn cost(&self, a: usize, b: usize) -> usize {
+        self.step + self.level_delta(a, b).unsigned_abs() as usize * self.level_change
+    }
+}
+
+/// A single step of a route
+#[derive(Clone, Copy, Debug)]
+pub struct Step {
+    pub pos: (usize, usize),
+
+    /// The change in level compared to the previous step
+    pub change: i32,
+}
+
+/// The cheapest route through a maze
+pub struct Route {
+    pub cost: usize,
+
+    /// All steps from the source to the target (including both)
+    pub steps: Vec<Step>,
+}
+
+pub struct Maze {
+    grid: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+impl Maze {
+    pub fn parse(input: &str) -> Self {
+        let lines = input.lines().collect::<Vec<_>>();
+        let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+        let height = lines.len();
+
+        // pad short lines with walls
+        let mut grid = vec![b'#'; width * height];
+        for (y, l) in lines.into_iter().enumerate() {
+            grid[y * width..y * width + l.len()].copy_from_slice(l.as_bytes());
+        }
+
+        Self {
+            grid,
+            width,
+            height,
+        }
+    }
+
+    /// Get the cell at (x, y) or `None` if the position is out of bounds
+    pub fn get(&self, x: i32, y: i32) -> Option<u8> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            None
+        } else {
+            Some(self.grid[y as usize * self.width + x as usize])
+        }
+    }
+
+    /// Get the level of the cell at (x, y) or `None` if it is out of bounds
+    /// or not walkable. Start and end cells are at level 0.
+    pub fn level(&self, x: i32, y: i32) -> Option<usize> {
+        match self.get(x, y)? {
+            b'S' | b'E' => Some(0),
+            c @ b'0'..=b'9' => Some((c - b'0') as usize),
+            _ => None,
+        }
+    }
+
+    /// Find all cells with the given symbol
+    pub fn find(&self, c: u8) -> Vec<(usize, usize)> {
+        self.grid
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| **g == c)
+            .map(|(i, _)| (i % self.width, i / self.width))
+            .collect()
+    }
+
+    /// Find the cheapest route from any of the given sources to any of the
+    /// given targets using Dijkstra's algorithm
+    pub fn shortest_route(
+        &self,
+        sources: &[(usize, usize)],
+        targets: &[(usize, usize)],
+        model: &CostModel,
+    ) -> Option<Route> {
+        let mut is_target = vec![false; self.grid.len()];
+        for &(x, y) in targets {
+            is_target[y * self.width + x] = true;
+        }
+
+        let mut dist = vec![usize::MAX; self.grid.len()];
+        let mut prev = vec![usize::MAX; self.grid.len()];
+        let mut queue = BinaryHeap::new();
+        for &(x, y) in sources {
+            dist[y * self.width + x] = 0;
+            queue.push(Reverse((0, x, y)));
+        }
+
+        while let Some(Reverse((cost, x, y))) = queue.pop() {
+            let i = y * self.width + x;
+            if cost > dist[i] {
+                continue;
+            }
+
+            if is_target[i] {
+                return Some(self.reconstruct(i, cost, &prev, model));
+            }
+
+            let level = self.level(x as i32, y as i32).unwrap();
+            for (dx, dy) in DIRS {
+                let nx = x as i32 + dx;
+                let ny = y as i32 + dy;
+                let Some(next_level) = self.level(nx, ny) else {
+                    continue;
+                };
+                let (nx, ny) = (nx as usize, ny as usize);
+                let ni = ny * self.width + nx;
+                let ncost = cost + model.cost(level, next_level);
+                if ncost < dist[ni] {
+                    dist[ni] = ncost;
+                    prev[ni] = i;
+                    queue.push(Reverse((ncost, nx, ny)));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Follow the predecessors from the given target back to a source
+    fn reconstruct(&self, target: usize, cost: usize, prev: &[usize], model: &CostModel) -> Route {
+        let mut cells = vec![target];
+        while prev[cells[cells.len() - 1]] != usize::MAX {
+            cells.push(prev[cells[cells.len() - 1]]);
+        }
+        cells.reverse();
+
+        let level = |i: usize| {
+            self.level((i % self.width) as i32, (i / self.width) as i32)
+                .unwrap()
+        };
+        let steps = cells
+            .iter()
+            .enumerate()
+            .map(|(j, &i)| Step {
+                pos: (i % self.width, i / self.width),
+                change: if j == 0 {
+                    0
+                } else {
+                    model.level_delta(level(cells[j - 1]), level(i))
+                },
+            })
+            .collect();
+
+        Route { cost, steps }
+    }
+
+    /// Render the maze with the given route on top. Each cell of the route is
+    /// replaced by `+` if the level went up, `-` if it went down, or `=` if
+    /// it stayed the same. Start and end cells are kept.
+    pub fn render(&self, route: &Route) -> String {
+        let mut grid = self.grid.clone();
+        for s in &route.steps {
+            let i = s.pos.1 * self.width + s.pos.0;
+            if grid[i] != b'S' && grid[i] != b'E' {
+                grid[i] = match s.change {
+                    0 => b'=',
+                    c if c > 0 => b'+',
+                    _ => b'-',
+                };
+            }
+        }
+        grid.chunks(self.width)
+            .map(|row| String::from_utf8_lossy(row))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: CostModel = CostModel {
+        levels: 10,
+        step: 1,
+        level_change: 1,
+    };
+
+    /// Find the cheapest route from `S` to `E` and check that its cost is
+    /// the sum of the costs of its steps
+    fn route(input: &str) -> Route {
+        let maze = Maze::parse(input);
+        let route = maze
+            .shortest_route(&maze.find(b'S'), &maze.find(b'E'), &MODEL)
+            .unwrap();
+        let (x, y) = route.steps[0].pos;
+        assert_eq!(maze.get(x as i32, y as i32), Some(b'S'));
+        let (x, y) = route.steps[route.steps.len() - 1].pos;
+        assert_eq!(maze.get(x as i32, y as i32), Some(b'E'));
+        assert_eq!(
+            route.cost,
+            route.steps[1..]
+                .iter()
+                .map(|s| MODEL.step + s.change.unsigned_abs() as usize * MODEL.level_change)
+                .sum::<usize>()
+        );
+        route
+    }
+
+    #[test]
+    fn level_wraparound() {
+        assert_eq!(MODEL.level_delta(1, 9), -2);
+        assert_eq!(MODEL.level_delta(9, 1), 2);
+        assert_eq!(MODEL.cost(0, 5), 6);
+    }
+
+    #[test]
+    fn single_start() {
+        let route = route("#######\n#6769##\nS50505E\n#97434#\n#######");
+        assert_eq!(route.cost, 28);
+    }
+
+    #[test]
+    fn multiple_starts() {
+        let route = route(
+            "SSSSSSSSSSS
+S674345621S
+S###6#4#18S
+S53#6#4532S
+S5450E0485S
+S##7154532S
+S2##314#18S
+S971595#34S
+SSSSSSSSSSS",
+        );
+        assert_eq!(route.cost, 14);
+    }
+
+    #[test]
+    fn no_route() {
+        let maze = Maze::parse("S#E");
+        assert!(
+            maze.shortest_route(&maze.find(b'S'), &maze.find(b'E'), &MODEL)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+pub type Pos = (i32, i32, i32);
+
+const CUBE: [Pos; 6] = [
+    (0, 1, 0),
+    (0, -1, 0),
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
+
+/// A plant consisting of segments in 3D space. Only occupied positions are
+/// stored, so the plant can grow in any direction without a size limit.
+pub struct Plant {
+    segments: HashSet<Pos>,
+    leaves: Vec<Pos>,
+}
+
+impl Plant {
+    /// Grow a plant from lines of comma-separated instructions such as
+    /// `U5,R3,D2,L5,U4,R5,D2`. Each line starts at the origin and ends in a
+    /// leaf.
+    pub fn grow(input: &str) -> Self {
+        let mut segments = HashSet::new();
+        let mut leaves = Vec::new();
+
+        for l in input.lines() {
+            let mut pos = (0, 0, 0);
+            for i in l.trim().split(',') {
+                let dir = match &i[0..1] {
+                    "U" => (0, 1, 0),
+                    "D" => (0, -1, 0),
+                    "R" => (1, 0, 0),
+                    "L" => (-1, 0, 0),
+                    "F" => (0, 0, 1),
+                    "B" => (0, 0, -1),
+                    _ => panic!("Unknown direction: {i}"),
+                };
+
+                let dist = i[1..].parse::<i32>().unwrap();
+                for _ in 0..dist {
+                    pos = (pos.0 + dir.0, pos.1 + dir.1, pos.2 + dir.2);
+                    segments.insert(pos);
+                }
+            }
+            leaves.push(pos);
+        }
+
+        Self { segments, leaves }
+    }
+
+    /// The height of the highest segment
+    pub fn height(&self) -> i32 {
+        self.segments.iter().map(|s| s.1).max().unwrap_or(0).max(0)
+    }
+
+    /// The number of distinct segments
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Calculate the murkiness of each trunk segment. The trunk consists of
+    /// all segments on the line through the origin along the given unit
+    /// `axis`. Trunk segments are identified by their position along this
+    /// axis. Only segments that can be reached from all leaves are returned.
+    ///
+    /// All leaves are searched from at once in a single breadth-first pass.
+    /// Each segment keeps a bitset of the leaves that have reached it, and in
+    /// every round the leaves that newly reach a segment are passed on to
+    /// its neighbors, so the distances of 64 leaves are accumulated with one
+    /// word operation.
+    pub fn murkiness(&self, axis: Pos) -> BTreeMap<i32, usize> {
+        assert_eq!(
+            axis.0.abs() + axis.1.abs() + axis.2.abs(),
+            1,
+            "Trunk axis must be a unit vector"
+        );
+
+        let mut cells = self.segments.iter().copied().collect::<Vec<_>>();
+        cells.sort_unstable();
+        let ids = cells
+            .iter()
+            .enumerate()
+            .map(|(i, &p)| (p, i))
+            .collect::<HashMap<_, _>>();
+        let neighbors = cells
+            .iter()
+            .map(|p| {
+                CUBE.iter()
+                    .filter_map(|d| ids.get(&(p.0 + d.0, p.1 + d.1, p.2 + d.2)).copied())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // `seen` holds the leaves that have reached each segment and `fresh`
+        // the ones that reached it in the last round
+        let words = self.leaves.len().div_ceil(64);
+        let mut seen = vec![0u64; cells.len() * words];
+        let mut fresh = vec![0u64; cells.len() * words];
+        let mut next = vec![0u64; cells.len() * words];
+        let mut frontier = Vec::new();
+        for (i, leaf) in self.leaves.iter().enumerate() {
+            let Some(&c) = ids.get(leaf) else {
+                return BTreeMap::new();
+            };
+            if fresh[c * words..(c + 1) * words].iter().all(|&w| w == 0) {
+                frontier.push(c);
+            }
+            seen[c * words + i / 64] |= 1 << (i % 64);
+            fresh[c * words + i / 64] |= 1 << (i % 64);
+        }
+
+        // the sum of the distances to the leaves that have reached each
+        // segment and their number
+        let mut total = vec![0usize; cells.len()];
+        let mut reached = vec![0usize; cells.len()];
+        for &c in &frontier {
+            reached[c] = fresh[c * words..(c + 1) * words]
+                .iter()
+                .map(|w| w.count_ones() as usize)
+                .sum();
+        }
+
+        let mut upcoming = Vec::new();
+        let mut queued = vec![false; cells.len()];
+        let mut dist = 0;
+        while !frontier.is_empty() {
+            dist += 1;
+            for &c in &frontier {
+                let bits = &fresh[c * words..(c + 1) * words];
+                for &n in &neighbors[c] {
+                    let mut any = 0;
+                    let known = &seen[n * words..(n + 1) * words];
+                    for ((out, &b), &k) in next[n * words..(n + 1) * words]
+                        .iter_mut()
+                        .zip(bits)
+                        .zip(known)
+                    {
+                        *out |= b & !k;
+                        any |= b & !k;
+                    }
+                    if any != 0 && !queued[n] {
+                        queued[n] = true;
+                        upcoming.push(n);
+                    }
+                }
+            }
+
+            for &c in &frontier {
+                fresh[c * words..(c + 1) * words].fill(0);
+            }
+            for &n in &upcoming {
+                queued[n] = false;
+                let mut count = 0;
+                for w in 0..words {
+                    seen[n * words + w] |= next[n * words + w];
+                    count += next[n * words + w].count_ones() as usize;
+                }
+                total[n] += dist * count;
+                reached[n] += count;
+            }
+            std::mem::swap(&mut fresh, &mut next);
+            std::mem::swap(&mut frontier, &mut upcoming);
+            upcoming.clear();
+        }
+
+        cells
+            .iter()
+            .enumerate()
+            .filter(|&(c, _)| reached[c] == self.leaves.len())
+            .filter_map(|(c, &p)| {
+                let k = p.0 * axis.0 + p.1 * axis.1 + p.2 * axis.2;
+                ((k * axis.0, k * axis.1, k * axis.2) == p).then_some((k, total[c]))
+            })
+            .collect()
+    }
+
+    /// Find the trunk segment with the lowest murkiness along the given axis.
+    /// Returns its position along the axis and its murkiness.
+    pub fn best_tap(&self, axis: Pos) -> Option<(i32, usize)> {
+        self.murkiness(axis)
+            .into_iter()
+            .min_by_key(|&(k, m)| (m, k))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "U20,L1,B1,L2,B1,R2,L1,F1,U1
+U10,F1,B1,R1,L1,B1,L1,F1,R2,U1
+U30,L2,F1,R1,B1,R1,F2,U1,F1
+U25,R1,L2,B1,U1,R2,F1,L2
+U16,L1,B1,L1,B3,L1,B1,F1";
+
+    #[test]
+    fn best_tap() {
+        let plant = Plant::grow("U5,R3,D2,L5,U4,R5,D2\nU6,L1,D2,R3,U2,L1");
+        assert_eq!(plant.best_tap((0, 1, 0)).map(|(_, m)| m), Some(5));
+        let plant = Plant::grow(EXAMPLE);
+        assert_eq!(plant.best_tap((0, 1, 0)).map(|(_, m)| m), Some(46));
+    }
+
+    #[test]
+    fn matches_one_search_per_leaf() {
+        let plant = Plant::grow(EXAMPLE);
+        let leaves = plant
+            .leaves
+            .iter()
+            .map(|&leaf| {
+                let mut dist = HashMap::from([(leaf, 0)]);
+                let mut queue = std::collections::VecDeque::from([leaf]);
+                while let Some(p) = queue.pop_front() {
+                    for d in CUBE {
+                        let n = (p.0 + d.0, p.1 + d.1, p.2 + d.2);
+                        if plant.segments.contains(&n) && !dist.contains_key(&n) {
+                            dist.insert(n, dist[&p] + 1);
+                            queue.push_back(n);
+                        }
+                    }
+                }
+                dist
+            })
+            .collect::<Vec<_>>();
+        let expected = (1..=30)
+            .map(|k| (k, leaves.iter().map(|d| d[&(0, k, 0)]).sum::<usize>()))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(plant.murkiness((0, 1, 0)), expected);
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+const DIRS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+/// The maximum number of herb types (one bit per type in a `u128`). Planning
+/// a tour with that many types would not be feasible anyway.
+const MAX_TYPES: usize = 128;
+
+#[derive(Debug)]
+pub enum MapError {
+    /// The top row does not contain an open cell
+    MissingEntrance,
+
+    /// The map contains more than `MAX_TYPES` herb types
+    TooManyTypes(usize),
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::MissingEntrance => write!(f, "no entrance found in the top row"),
+            MapError::TooManyTypes(n) => {
+                write!(
+                    f,
+                    "{n} herb types found but at most {MAX_TYPES} are supported"
+                )
+            }
+        }
+    }
+}
+
+/// A route that starts at the entrance, collects one herb of each type, and
+/// returns to the entrance
+pub struct Tour {
+    /// The total number of steps
+    pub steps: usize,
+
+    /// The herbs in the order in which they are collected, together with
+    /// their positions
+    pub order: Vec<(u8, (usize, usize))>,
+
+    /// All cells visited, including the entrance at the beginning and the end
+    pub path: Vec<(usize, usize)>,
+}
+
+/// A map of a grid maze containing herbs. Herbs are identified by letters.
+/// Cells containing `#` (walls) or `~` (water) cannot be entered.
+pub struct HerbMap {
+    grid: Vec<u8>,
+    width: usize,
+    height: usize,
+    start: (usize, usize),
+
+    /// All herb types (in the order of their bits in a type mask)
+    types: Vec<u8>,
+}
+
+impl HerbMap {
+    /// Parse a map. The entrance is the first open cell in the top row.
+    pub fn parse(input: &str) -> Result<Self, MapError> {
+        let lines = input.lines().collect::<Vec<_>>();
+        let width = lines[0].len();
+        let height = lines.len();
+        let grid = lines
+            .into_iter()
+            .flat_map(|l| l.as_bytes().iter().copied())
+            .collect::<Vec<_>>();
+
+        let start = grid
+            .iter()
+            .take(width)
+            .position(|&c| c == b'.')
+            .ok_or(MapError::MissingEntrance)?;
+
+        let mut types = grid
+            .iter()
+            .copied()
+            .filter(|c| c.is_ascii_alphabetic())
+            .collect::<Vec<_>>();
+        types.sort_unstable();
+        types.dedup();
+        if types.len() > MAX_TYPES {
+            return Err(MapError::TooManyTypes(types.len()));
+        }
+
+        Ok(Self {
+            grid,
+            width,
+            height,
+            start: (start, 0),
+            types,
+        })
+    }
+
+    /// The number of distinct herb types on the map
+    pub fn herb_types(&self) -> usize {
+        self.types.len()
+    }
+
+    /// Perform a breadth-first search from the given cell. Returns the
+    /// distance to each cell and each cell's predecessor on a shortest path.
+    fn bfs(&self, (sx, sy): (usize, usize)) -> (Vec<usize>, Vec<usize>) {
+        let mut dist = vec![usize::MAX; self.grid.len()];
+        let mut prev = vec![usize::MAX; self.grid.len()];
+        let mut queue = VecDeque::new();
+        dist[sy * self.width + sx] = 0;
+        queue.push_back((sx, sy));
+
+        while let Some((x, y)) = queue.pop_front() {
+            let d = dist[y * self.width + x];
+            for (dx, dy) in DIRS {
+                let nx = x as i32 + dx;
+                let ny = y as i32 + dy;
+                if nx < 0 || ny < 0 || nx >= self.width as i32 || ny >= self.height as i32 {
+                    continue;
+                }
+                let ni = ny as usize * self.width + nx as usize;
+                if self.grid[ni] != b'#' && self.grid[ni] != b'~' && dist[ni] == usize::MAX {
+                    dist[ni] = d + 1;
+                    prev[ni] = y * self.width + x;
+                    queue.push_back((nx as usize, ny as usize));
+                }
+            }
+        }
+
+        (dist, prev)
+    }
+
+    /// Find the shortest tour that collects one herb of each type and returns
+    /// to the entrance. Returns `None` if there is no such tour.
+    ///
+    /// We calculate the distances between all herbs (and the entrance) and
+    /// then perform a dynamic programming search (Held-Karp) over states
+    /// consisting of the current herb and the set of herb types collected so
+    /// far. Paths may lead through other herbs, so this works for any map
+    /// layout. The states are processed in layers by the number of types
+    /// collected. Within a layer, each set of types gets a dense index when
+    /// it is first reached, so the states can be stored in flat vectors.
+    /// From each state, we only move on to the herbs of the types that have
+    /// not been collected yet.
+    pub fn plan(&self) -> Option<Tour> {
+        // node 0 is the entrance, all others are herbs
+        let mut nodes = vec![self.start];
+        let mut candidates = vec![Vec::new(); self.types.len()];
+        for (i, &c) in self.grid.iter().enumerate() {
+            if let Ok(t) = self.types.binary_search(&c) {
+                candidates[t].push(nodes.len());
+                nodes.push((i % self.width, i / self.width));
+            }
+        }
+        let n = nodes.len();
+
+        let searches = nodes.iter().map(|&p| self.bfs(p)).collect::<Vec<_>>();
+        let dist = searches
+            .iter()
+            .flat_map(|(d, _)| nodes.iter().map(|&(x, y)| d[y * self.width + x]))
+            .collect::<Vec<_>>();
+
+        // the first layer only contains the entrance with no types collected
+        let mut layers = vec![Layer::new(0, n)];
+        layers[0].steps[0] = 0;
+
+        let mut sources = Vec::new();
+        for _ in 0..self.types.len() {
+            let layer = &layers[layers.len() - 1];
+            let mut next_layer = Layer::default();
+            for (m, &mask) in layer.masks.iter().enumerate() {
+                sources.clear();
+                sources.extend((0..n).filter(|&i| layer.steps[m * n + i] != usize::MAX));
+
+                for (t, c) in candidates.iter().enumerate() {
+                    if mask & (1 << t) != 0 {
+                        continue;
+                    }
+                    let id = next_layer.intern(mask | (1 << t), n);
+                    for &from in &sources {
+                        let steps = layer.steps[m * n + from];
+                        for &to in c {
+                            let d = dist[from * n + to];
+                            let state = id * n + to;
+                            if d != usize::MAX && steps + d < next_layer.steps[state] {
+                                next_layer.steps[state] = steps + d;
+                                next_layer.prev[state] = m * n + from;
+                            }
+                        }
+                    }
+                }
+            }
+            layers.push(next_layer);
+        }
+
+        // return to the entrance
+        let last = &layers[layers.len() - 1];
+        let (steps, end) = (0..n)
+            .filter(|&i| last.steps[i] != usize::MAX && dist[i * n] != usize::MAX)
+            .map(|i| (last.steps[i] + dist[i * n], i))
+            .min()?;
+
+        // reconstruct the order in which the herbs are visited
+        let mut visits = vec![0, end];
+        let mut state = end;
+        for layer in layers[1..].iter().rev() {
+            state = layer.prev[state];
+            visits.push(state % n);
+        }
+        visits.reverse();
+
+        // follow the shortest paths between consecutive visits
+        let mut path = vec![self.start];
+        for w in visits.windows(2) {
+            let (x, y) = nodes[w[1]];
+            let mut segment = Vec::new();
+            let mut i = y * self.width + x;
+            while searches[w[0]].1[i] != usize::MAX {
+                segment.push((i % self.width, i / self.width));
+                i = searches[w[0]].1[i];
+            }
+            segment.reverse();
+            path.extend(segment);
+        }
+
+        let order = visits[1..visits.len() - 1]
+            .iter()
+            .map(|&n| {
+                let (x, y) = nodes[n];
+                (self.grid[y * self.width + x], (x, y))
+            })
+            .collect();
+
+        Some(Tour { steps, order, path })
+    }
+
+    /// Render the map with the path of the given tour on top. Open cells on
+    /// the path are replaced by `*`.
+    pub fn render(&self, tour: &Tour) -> String {
+        let mut grid = self.grid.clone();
+        for &(x, y) in &tour.path {
+            if grid[y * self.width + x] == b'.' {
+                grid[y * self.width + x] = b'*';
+            }
+        }
+        grid.chunks(self.width)
+            .map(|row| String::from_utf8_lossy(row))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// All states of the search in which the same number of herb types have been
+/// collected
+#[derive(Default)]
+struct Layer {
+    /// The sets of types collected, as bit masks
+    masks: Vec<u128>,
+    ids: HashMap<u128, usize>,
+
+    /// The minimum number of steps for each state or `usize::MAX` if the
+    /// state has not been reached. The state of node `i` with the set of
+    /// types `masks[m]` has the index `m * n + i`.
+    steps: Vec<usize>,
+
+    /// The index of each state's predecessor in the previous layer
+    prev: Vec<usize>,
+}
+
+impl Layer {
+    fn new(mask: u128, n: usize) -> Self {
+        let mut layer = Self::default();
+        layer.intern(mask, n);
+        layer
+    }
+
+    /// Get the index of the given set of types or add it to the layer
+    fn intern(&mut self, mask: u128, n: usize) -> usize {
+        let len = self.masks.len();
+        let id = *self.ids.entry(mask).or_insert(len);
+        if id == len {
+            self.masks.push(mask);
+            self.steps.resize(self.steps.len() + n, usize::MAX);
+            self.prev.resize(self.prev.len() + n, usize::MAX);
+        }
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plan a tour and check that it is a closed walk through all types
+    fn plan(input: &str) -> Option<Tour> {
+        let map = HerbMap::parse(input).unwrap();
+        let tour = map.plan()?;
+        assert_eq!(tour.order.len(), map.herb_types());
+        assert_eq!(tour.path.len() - 1, tour.steps);
+        assert_eq!(tour.path.first(), Some(&map.start));
+        assert_eq!(tour.path.last(), Some(&map.start));
+        assert!(
+            tour.path
+                .windows(2)
+                .all(|w| w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1) == 1)
+        );
+        for &(h, (x, y)) in &tour.order {
+            assert!(tour.path.contains(&(x, y)));
+            assert_eq!(map.grid[y * map.width + x], h);
+        }
+        Some(tour)
+    }
+
+    #[test]
+    fn example() {
+        let tour = plan(
+            "#####.#####
+#.........#
+#.######.##
+#.........#
+###.#.#####
+#H.......H#
+###########",
+        );
+        assert_eq!(tour.map(|t| t.steps), Some(26));
+    }
+
+    #[test]
+    fn herbs_behind_water() {
+        let tour = plan("#.#####\n#...A.#\n#~~~~~#\n#.B..C#\n#######");
+        assert!(tour.is_none());
+    }
+
+    #[test]
+    fn paths_through_herbs() {
+        let tour = plan("#.###\n#.BA#\n#####").unwrap();
+        assert_eq!(tour.steps, 6);
+    }
+
+    #[test]
+    fn missing_entrance() {
+        assert!(matches!(
+            HerbMap::parse("###\n#A#\n###"),
+            Err(MapError::MissingEntrance)
+        ));
+    }
+}
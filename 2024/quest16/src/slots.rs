@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum SlotError {
+    /// The number of combinations of wheel positions does not fit in a
+    /// `u64`, so positions cannot be encoded
+    TooManyPositions,
+
+    /// The number of pulls after which all wheels are back at their initial
+    /// positions does not fit in a `u64`
+    CycleTooLong,
+}
+
+impl fmt::Display for SlotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SlotError::TooManyPositions => write!(f, "too many combinations of wheel positions"),
+            SlotError::CycleTooLong => write!(f, "cycle length does not fit in 64 bits"),
+        }
+    }
+}
+
+/// The ways the left lever can be pulled before each pull of the right lever
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeftLever {
+    Up,
+    Down,
+    None,
+}
+
+const LEVERS: [LeftLever; 3] = [LeftLever::Up, LeftLever::Down, LeftLever::None];
+
+/// The best or worst result of a sequence of pulls
+pub struct Policy {
+    pub coins: u64,
+
+    /// The left lever action before each pull of the right lever
+    pub levers: Vec<LeftLever>,
+}
+
+/// Greatest common divisor
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Least common multiple or `None` if it does not fit in a `u64`
+fn lcm(a: u64, b: u64) -> Option<u64> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// A slot machine with wheels of faces. Each pull of the right lever
+/// advances every wheel by its own number of steps.
+pub struct SlotMachine {
+    wheels: Vec<Vec<[u8; 3]>>,
+    steps: Vec<usize>,
+}
+
+impl SlotMachine {
+    /// Parse a comma-separated list of steps followed by an empty line and
+    /// the wheels. Faces are three characters wide and separated by a space.
+    /// Fails if the positions of all wheels cannot be encoded in a `u64`.
+    pub fn parse(input: &str) -> Result<Self, SlotError> {
+        let (steps, str_wheels) = input.split_once("\n\n").unwrap();
+        let steps = steps
+            .trim()
+            .split(',')
+            .map(|i| i.parse::<usize>().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut wheels = vec![Vec::new(); steps.len()];
+        for s in str_wheels.lines() {
+            for (n, w) in wheels.iter_mut().enumerate() {
+                let Some(face) = s.get(n * 4..n * 4 + 3) else {
+                    break;
+                };
+                if !face.trim().is_empty() {
+                    w.push(face.as_bytes().try_into().unwrap());
+                }
+            }
+        }
+
+        wheels
+            .iter()
+            .try_fold(1u64, |acc, w| acc.checked_mul(w.len() as u64))
+            .ok_or(SlotError::TooManyPositions)?;
+
+        Ok(Self { wheels, steps })
+    }
+
+    /// Encode wheel positions as a single mixed-radix number. This cannot
+    /// overflow because the number of combinations is checked when parsing.
+    fn encode(&self, positions: &[usize]) -> u64 {
+        self.wheels
+            .iter()
+            .zip(positions)
+            .rev()
+            .fold(0, |acc, (w, &p)| acc * w.len() as u64 + p as u64)
+    }
+
+    /// Decode wheel positions encoded with [`Self::encode`]
+    fn decode(&self, mut code: u64, positions: &mut [usize]) {
+        for (w, p) in self.wheels.iter().zip(positions.iter_mut()) {
+            *p = (code % w.len() as u64) as usize;
+            code /= w.len() as u64;
+        }
+    }
+
+    /// Get the positions of all wheels after pulling the left lever the given
+    /// way and then the right lever once
+    fn pull(&self, positions: &mut [usize], left: LeftLever) {
+        for ((w, &s), p) in self.wheels.iter().zip(&self.steps).zip(positions) {
+            let dist = match left {
+                LeftLever::Up => 1,
+                LeftLever::Down => w.len() - 1,
+                LeftLever::None => 0,
+            };
+            *p = (*p + dist + s) % w.len();
+        }
+    }
+
+    /// Count the coins won with the wheels at the given positions. Each
+    /// symbol on the left and right of the faces wins one coin for every
+    /// occurrence beyond the second.
+    pub fn coins(&self, positions: &[usize]) -> u64 {
+        let mut counts = [0u64; 256];
+        for (w, &p) in self.wheels.iter().zip(positions) {
+            counts[w[p][0] as usize] += 1;
+            counts[w[p][2] as usize] += 1;
+        }
+        counts.into_iter().map(|c| c.saturating_sub(2)).sum()
+    }
+
+    /// Get the faces shown after the given number of pulls of the right lever
+    pub fn faces(&self, pulls: u64) -> Vec<&str> {
+        self.wheels
+            .iter()
+            .zip(&self.steps)
+            .map(|(w, &s)| {
+                let p = (s as u64 % w.len() as u64 * (pulls % w.len() as u64)) % w.len() as u64;
+                std::str::from_utf8(&w[p as usize]).unwrap()
+            })
+            .collect()
+    }
+
+    /// The number of pulls of the right lever after which all wheels are back
+    /// at their initial positions. Each wheel returns after
+    /// `len / gcd(len, step)` pulls, so the machine returns after the least
+    /// common multiple of these periods.
+    pub fn cycle_length(&self) -> Result<u64, SlotError> {
+        self.wheels
+            .iter()
+            .zip(&self.steps)
+            .map(|(w, &s)| {
+                let len = w.len() as u64;
+                len / gcd(len, s as u64)
+            })
+            .try_fold(1, lcm)
+            .ok_or(SlotError::CycleTooLong)
+    }
+
+    /// Calculate the total number of coins won after pulling only the right
+    /// lever the given number of times. Only one cycle is simulated.
+    pub fn total_coins(&self, pulls: u64) -> Result<u64, SlotError> {
+        let cycle = self.cycle_length()?;
+        let remainder = pulls % cycle;
+
+        let mut positions = vec![0; self.wheels.len()];
+        let mut per_cycle = 0;
+        let mut prefix = 0;
+        for i in 0..cycle.min(pulls) {
+            if i == remainder {
+                prefix = per_cycle;
+            }
+            self.pull(&mut positions, LeftLever::None);
+            per_cycle += self.coins(&positions);
+        }
+        if pulls < cycle {
+            return Ok(per_cycle);
+        }
+
+        Ok(pulls / cycle * per_cycle + prefix)
+    }
+
+    /// Find the lever sequences winning the fewest and the most coins in the
+    /// given number of pulls. Returns the minimum and the maximum policy.
+    ///
+    /// We calculate the reachable positions layer by layer and remember, for
+    /// each position, the best predecessor and lever action, so that the
+    /// sequences can be reconstructed afterwards.
+    pub fn optimize(&self, pulls: usize) -> (Policy, Policy) {
+        // per layer: code -> (min coins, predecessor, lever), (max coins, ...)
+        type Entry = (u64, u64, LeftLever);
+        let mut layers: Vec<HashMap<u64, (Entry, Entry)>> = Vec::with_capacity(pulls + 1);
+        let start = (0, 0, LeftLever::None);
+        layers.push(HashMap::from([(
+            self.encode(&vec![0; self.wheels.len()]),
+            (start, start),
+        )]));
+
+        let mut positions = vec![0; self.wheels.len()];
+        for _ in 0..pulls {
+            let mut next: HashMap<u64, (Entry, Entry)> = HashMap::new();
+            for (&code, &((min, ..), (max, ..))) in &layers[layers.len() - 1] {
+                for left in LEVERS {
+                    self.decode(code, &mut positions);
+                    self.pull(&mut positions, left);
+                    let coins = self.coins(&positions);
+                    let lo = (min + coins, code, left);
+                    let hi = (max + coins, code, left);
+                    next.entry(self.encode(&positions))
+                        .and_modify(|(a, b)| {
+                            if lo.0 < a.0 {
+                                *a = lo;
+                            }
+                            if hi.0 > b.0 {
+                                *b = hi;
+                            }
+                        })
+                        .or_insert((lo, hi));
+                }
+            }
+            layers.push(next);
+        }
+
+        let last = &layers[pulls];
+        let (&min_code, &(min, _)) = last.iter().min_by_key(|(_, (a, _))| a.0).unwrap();
+        let (&max_code, &(_, max)) = last.iter().max_by_key(|(_, (_, b))| b.0).unwrap();
+
+        // follow the predecessors back to the initial positions
+        let reconstruct = |mut code: u64, pick: fn(&(Entry, Entry)) -> Entry| {
+            let mut levers = Vec::with_capacity(pulls);
+            for layer in layers[1..].iter().rev() {
+                let (_, prev, left) = pick(&layer[&code]);
+                levers.push(left);
+                code = prev;
+            }
+            levers.reverse();
+            levers
+        };
+
+        (
+            Policy {
+                coins: min.0,
+                levers: reconstruct(min_code, |e| e.0),
+            },
+            Policy {
+                coins: max.0,
+                levers: reconstruct(max_code, |e| e.1),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1,2,3
+
+^_^ -.- ^,-
+>.- ^_^ >.<
+-_- -.- >.<
+    -.^ ^_^
+    >.>";
+
+    #[test]
+    fn right_lever_only() {
+        let machine = SlotMachine::parse(EXAMPLE).unwrap();
+        assert_eq!(machine.faces(100), [">.-", "-.-", "^,-"]);
+        assert_eq!(machine.total_coins(202420242024).unwrap(), 280014668134);
+    }
+
+    #[test]
+    fn policies() {
+        let machine = SlotMachine::parse(EXAMPLE).unwrap();
+        let (min, max) = machine.optimize(256);
+        assert_eq!((max.coins, min.coins), (545, 160));
+
+        // replaying the lever sequences must win the same number of coins
+        let play = |levers: &[LeftLever]| {
+            let mut positions = vec![0; machine.wheels.len()];
+            levers
+                .iter()
+                .map(|&l| {
+                    machine.pull(&mut positions, l);
+                    machine.coins(&positions)
+                })
+                .sum::<u64>()
+        };
+        assert_eq!(play(&min.levers), min.coins);
+        assert_eq!(play(&max.levers), max.coins);
+    }
+
+    #[test]
+    fn overflow() {
+        // 17 wheels with 16 faces each have 2^68 combinations of positions
+        let input = format!("{}\n\n", vec!["1"; 17].join(","))
+            + &vec![vec!["^_^"; 17].join(" "); 16].join("\n");
+        assert!(matches!(
+            SlotMachine::parse(&input),
+            Err(SlotError::TooManyPositions)
+        ));
+        assert_eq!(lcm(1 << 40, 3 << 30), Some(3 << 40));
+        assert_eq!(lcm(1 << 40, 3 << 24 | 1), None);
+    }
+}
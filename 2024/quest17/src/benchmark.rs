@@ -0,0 +1,145 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::time::Instant;
+
+use crate::bucket_queue::{BucketQueue, IndexedBucketQueue};
+use crate::mst::{self, Edge};
+
+/// Run the given function and print how long it took
+fn time<R>(name: &str, f: impl FnOnce() -> R) -> R {
+    let start = Instant::now();
+    let result = f();
+    eprintln!("{name:<32} {:?}", start.elapsed());
+    result
+}
+
+/// Build adjacency lists from the given edges
+fn neighbors(n: usize, edges: &[Edge]) -> Vec<Vec<(usize, usize)>> {
+    let mut result = vec![Vec::new(); n];
+    for e in edges {
+        result[e.from].push((e.to, e.weight));
+        result[e.to].push((e.from, e.weight));
+    }
+    result
+}
+
+/// Calculate the weight of a minimum spanning forest with Prim's algorithm
+/// using a binary heap
+fn prim_binary_heap(neighbors: &[Vec<(usize, usize)>]) -> usize {
+    let mut in_tree = vec![false; neighbors.len()];
+    let mut total = 0;
+    for start in 0..neighbors.len() {
+        if in_tree[start] {
+            continue;
+        }
+        let mut queue = BinaryHeap::from([Reverse((0, start))]);
+        while let Some(Reverse((weight, s))) = queue.pop() {
+            if in_tree[s] {
+                continue;
+            }
+            in_tree[s] = true;
+            total += weight;
+            for &(to, w) in &neighbors[s] {
+                if !in_tree[to] {
+                    queue.push(Reverse((w, to)));
+                }
+            }
+        }
+    }
+    total
+}
+
+/// Calculate shortest distances from node 0 with Dijkstra's algorithm using a
+/// binary heap
+fn dijkstra_binary_heap(neighbors: &[Vec<(usize, usize)>]) -> Vec<usize> {
+    let mut dist = vec![usize::MAX; neighbors.len()];
+    let mut queue = BinaryHeap::from([Reverse((0, 0))]);
+    dist[0] = 0;
+    while let Some(Reverse((d, s))) = queue.pop() {
+        if d > dist[s] {
+            continue;
+        }
+        for &(to, w) in &neighbors[s] {
+            if d + w < dist[to] {
+                dist[to] = d + w;
+                queue.push(Reverse((d + w, to)));
+            }
+        }
+    }
+    dist
+}
+
+/// Calculate shortest distances from node 0 with Dial's algorithm. Returns
+/// the distances and the maximum number of queued items.
+fn dijkstra_bucket_queue(neighbors: &[Vec<(usize, usize)>]) -> (Vec<usize>, usize) {
+    let mut dist = vec![usize::MAX; neighbors.len()];
+    let mut queue = BucketQueue::new();
+    let mut peak = 1;
+    queue.insert(0, 0);
+    dist[0] = 0;
+    while let Some((d, s)) = queue.pop() {
+        if d > dist[s] {
+            continue;
+        }
+        for &(to, w) in &neighbors[s] {
+            if d + w < dist[to] {
+                dist[to] = d + w;
+                queue.insert(d + w, to);
+            }
+        }
+        peak = peak.max(queue.len());
+    }
+    (dist, peak)
+}
+
+/// Calculate shortest distances from node 0 with Dial's algorithm, using
+/// decrease-key instead of inserting nodes multiple times
+fn dijkstra_indexed_bucket_queue(neighbors: &[Vec<(usize, usize)>]) -> Vec<usize> {
+    let mut dist = vec![usize::MAX; neighbors.len()];
+    let mut queue = IndexedBucketQueue::new(neighbors.len());
+    queue.decrease_key(0, 0);
+    dist[0] = 0;
+    while let Some((d, s)) = queue.pop() {
+        for &(to, w) in &neighbors[s] {
+            if d + w < dist[to] {
+                dist[to] = d + w;
+                queue.decrease_key(to, d + w);
+            }
+        }
+    }
+    dist
+}
+
+/// Compare the bucket queues with a binary heap on the graph of all star
+/// pairs and print the timings to stderr
+pub fn run(stars: &[(i64, i64)], edges: &[Edge], cutoff: Option<usize>) {
+    let graph = edges
+        .iter()
+        .filter(|e| cutoff.is_none_or(|c| e.weight < c))
+        .copied()
+        .collect::<Vec<_>>();
+    let neighbors = neighbors(stars.len(), &graph);
+    eprintln!("{} stars, {} edges", stars.len(), graph.len());
+
+    let a = time("prim (binary heap)", || prim_binary_heap(&neighbors));
+    let b = time("prim (indexed bucket queue)", || {
+        mst::prim(stars.len(), &graph, None).weight()
+    });
+    let c = time("kruskal", || {
+        mst::kruskal(stars.len(), &graph, None).weight()
+    });
+    let d = time("manhattan sweep", || mst::manhattan(stars, cutoff).weight());
+    assert!(a == b && b == c && c == d, "Spanning forests differ");
+
+    let a = time("dijkstra (binary heap)", || {
+        dijkstra_binary_heap(&neighbors)
+    });
+    let (b, peak) = time("dijkstra (bucket queue)", || {
+        dijkstra_bucket_queue(&neighbors)
+    });
+    let c = time("dijkstra (indexed bucket queue)", || {
+        dijkstra_indexed_bucket_queue(&neighbors)
+    });
+    assert!(a == b && b == c, "Shortest distances differ");
+    eprintln!("peak bucket queue length: {peak}");
+}
@@ -1,34 +1,274 @@
+use std::collections::VecDeque;
+
+/// A priority queue for non-negative integer keys. Items are stored in one
+/// bucket per key, so inserting and popping take amortized constant time as
+/// long as the popped keys stay close together (as in Dial's variant of
+/// Dijkstra's algorithm). Items with the same key are popped in the order in
+/// which they were inserted.
+///
+/// The buckets form a ring in which the bucket of key `k` is at index
+/// `k % buckets.len()`. The ring only needs to span the keys currently in the
+/// queue, so in Dial's algorithm, where all queued keys lie within the
+/// largest edge weight of the lowest one, its size stays bounded no matter
+/// how large the distances get. It grows automatically if necessary.
 pub struct BucketQueue<T> {
-    queue: Vec<Vec<T>>,
-    last_bucket: usize,
+    buckets: Vec<VecDeque<T>>,
+
+    /// The lowest non-empty bucket (only valid if the queue is not empty)
+    first: usize,
+
+    /// An upper bound for the highest key in the queue (only valid if the
+    /// queue is not empty)
+    last: usize,
+
+    len: usize,
 }
 
-impl<T> BucketQueue<T>
-where
-    T: Clone,
-{
-    pub fn new(len: usize) -> Self {
+impl<T> Default for BucketQueue<T> {
+    fn default() -> Self {
         Self {
-            queue: vec![Vec::new(); len],
-            last_bucket: 0,
+            buckets: Vec::new(),
+            first: 0,
+            last: 0,
+            len: 0,
         }
     }
+}
+
+impl<T> BucketQueue<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-    pub fn insert(&mut self, bucket: usize, item: T) {
-        self.queue[bucket].push(item);
-        self.last_bucket = self.last_bucket.min(bucket);
+    /// The number of items in the queue
+    pub fn len(&self) -> usize {
+        self.len
     }
 
-    pub fn pop(&mut self) -> Option<T> {
-        let mut i = self.last_bucket;
-        while i < self.queue.len() && self.queue[i].is_empty() {
-            i += 1;
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Resize the ring so that it can hold at least `span` consecutive keys
+    /// and move all buckets to their new positions
+    fn grow(&mut self, span: usize) {
+        let size = span.max(self.buckets.len() * 2).next_power_of_two();
+        let mut buckets = Vec::with_capacity(size);
+        buckets.resize_with(size, VecDeque::new);
+        if !self.is_empty() {
+            let old = self.buckets.len();
+            for key in self.first..=self.last {
+                buckets[key % size] = std::mem::take(&mut self.buckets[key % old]);
+            }
         }
-        self.last_bucket = i;
-        if i == self.queue.len() {
-            None
+        self.buckets = buckets;
+    }
+
+    /// Insert an item with the given key. Keys lower than the last popped key
+    /// are allowed.
+    pub fn insert(&mut self, key: usize, item: T) {
+        let (first, last) = if self.is_empty() {
+            (key, key)
         } else {
-            Some(self.queue[i].swap_remove(0))
+            (self.first.min(key), self.last.max(key))
+        };
+        if last - first >= self.buckets.len() {
+            self.grow(last - first + 1);
+        }
+        self.first = first;
+        self.last = last;
+
+        let size = self.buckets.len();
+        self.buckets[key % size].push_back(item);
+        self.len += 1;
+    }
+
+    /// Get the item with the lowest key and the key itself without removing
+    /// the item from the queue
+    pub fn peek(&self) -> Option<(usize, &T)> {
+        if self.is_empty() {
+            return None;
+        }
+        self.buckets[self.first % self.buckets.len()]
+            .front()
+            .map(|item| (self.first, item))
+    }
+
+    /// Remove the item with the lowest key and return it together with its
+    /// key
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        if self.is_empty() {
+            return None;
+        }
+        let size = self.buckets.len();
+        let key = self.first;
+        let item = self.buckets[key % size].pop_front().unwrap();
+        self.len -= 1;
+
+        // advance to the next non-empty bucket
+        if !self.is_empty() {
+            while self.buckets[self.first % size].is_empty() {
+                self.first += 1;
+            }
+        }
+
+        Some((key, item))
+    }
+}
+
+/// A bucket queue for items identified by indexes `0..n`, in which the key
+/// of an item can be decreased. Outdated entries are not removed from their
+/// buckets but skipped when they reach the front of the queue.
+pub struct IndexedBucketQueue {
+    queue: BucketQueue<usize>,
+
+    /// The current key of each item or `None` if the item is not queued
+    keys: Vec<Option<usize>>,
+}
+
+impl IndexedBucketQueue {
+    pub fn new(n: usize) -> Self {
+        Self {
+            queue: BucketQueue::new(),
+            keys: vec![None; n],
+        }
+    }
+
+    /// Insert an item or decrease its key if it is already queued. Returns
+    /// `false` if the item is already queued with a key that is not greater
+    /// than the given one.
+    pub fn decrease_key(&mut self, item: usize, key: usize) -> bool {
+        if self.keys[item].is_some_and(|k| k <= key) {
+            return false;
+        }
+        self.keys[item] = Some(key);
+        self.queue.insert(key, item);
+
+        // rebuild the queue if it consists mostly of outdated entries
+        if self.queue.len() > 2 * self.keys.len() {
+            let mut queue = BucketQueue::new();
+            while let Some((k, i)) = self.queue.pop() {
+                if self.keys[i] == Some(k) {
+                    queue.insert(k, i);
+                }
+            }
+            self.queue = queue;
+        }
+
+        true
+    }
+
+    /// Remove the item with the lowest key and return it together with its
+    /// key
+    pub fn pop(&mut self) -> Option<(usize, usize)> {
+        // discard outdated entries
+        while let Some((key, &item)) = self.queue.peek() {
+            if self.keys[item] == Some(key) {
+                break;
+            }
+            self.queue.pop();
+        }
+
+        let (key, item) = self.queue.pop()?;
+        self.keys[item] = None;
+        Some((key, item))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    fn drain<T>(queue: &mut BucketQueue<T>) -> Vec<(usize, T)> {
+        std::iter::from_fn(|| queue.pop()).collect()
+    }
+
+    #[test]
+    fn fifo() {
+        let mut queue = BucketQueue::new();
+        for (key, item) in [(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd')] {
+            queue.insert(key, item);
+        }
+        assert_eq!(queue.peek(), Some((1, &'b')));
+        assert_eq!(drain(&mut queue), [(1, 'b'), (1, 'd'), (3, 'a'), (3, 'c')]);
+        assert!(queue.is_empty());
+        assert_eq!(queue.peek(), None);
+    }
+
+    #[test]
+    fn lower_keys_and_growth() {
+        let mut queue = BucketQueue::new();
+        queue.insert(100, 0);
+        assert_eq!(queue.pop(), Some((100, 0)));
+
+        // lower than the last popped key, and then a span larger than the
+        // ring, which forces it to grow while items are queued
+        queue.insert(5, 1);
+        queue.insert(7, 2);
+        queue.insert(1000, 3);
+        queue.insert(50, 4);
+        assert_eq!(queue.len(), 4);
+        assert_eq!(drain(&mut queue), [(5, 1), (7, 2), (50, 4), (1000, 3)]);
+    }
+
+    #[test]
+    fn decrease_key() {
+        let mut queue = IndexedBucketQueue::new(3);
+        assert!(queue.decrease_key(0, 10));
+        assert!(!queue.decrease_key(0, 12));
+        assert!(!queue.decrease_key(0, 10));
+        assert!(queue.decrease_key(0, 3));
+        assert!(queue.decrease_key(1, 3));
+        assert!(queue.decrease_key(2, 7));
+        assert_eq!(queue.pop(), Some((3, 0)));
+
+        // popped items can be queued again
+        assert!(queue.decrease_key(0, 5));
+        assert_eq!(queue.pop(), Some((3, 1)));
+        assert_eq!(queue.pop(), Some((5, 0)));
+        assert_eq!(queue.pop(), Some((7, 2)));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn outdated_entries() {
+        // decreasing the key over and over again forces the queue to be
+        // rebuilt without its outdated entries
+        let mut queue = IndexedBucketQueue::new(2);
+        queue.decrease_key(1, 60);
+        for key in (0..50).rev() {
+            assert!(queue.decrease_key(0, key));
+        }
+        assert!(queue.queue.len() <= 2 * 2 + 1);
+        assert_eq!(queue.pop(), Some((0, 0)));
+        assert_eq!(queue.pop(), Some((60, 1)));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn matches_binary_heap() {
+        let mut queue = BucketQueue::new();
+        let mut heap = BinaryHeap::new();
+        let mut last = 0;
+        for i in 0..10000usize {
+            if i % 3 > 0 {
+                // keys may be lower than the last popped one and the range
+                // of keys in the queue may exceed the size of the ring
+                let key = (last + i * 37 % 101).saturating_sub(10);
+                queue.insert(key, i);
+                heap.push(Reverse((key, i)));
+            } else {
+                // items with the same key must be popped in FIFO order
+                let expected = heap.pop().map(|Reverse(e)| e);
+                assert_eq!(queue.peek().map(|(k, &i)| (k, i)), expected);
+                assert_eq!(queue.pop(), expected);
+                if let Some((k, _)) = expected {
+                    last = k;
+                }
+            }
+            assert_eq!(queue.len(), heap.len());
         }
     }
 }
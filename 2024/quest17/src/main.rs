@@ -1,25 +1,26 @@
-use std::collections::HashSet;
 use std::fs;
 
-use bucket_queue::BucketQueue;
+use mst::Edge;
 
+#[cfg(feature = "benchmark")]
+mod benchmark;
 mod bucket_queue;
-
-#[derive(Clone, Copy)]
-struct Edge {
-    dist: usize,
-    to: usize,
-}
-
-struct Star {
-    x: usize,
-    y: usize,
-}
-
-impl Star {
-    fn dist(&self, other: &Star) -> usize {
-        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+mod mst;
+
+/// Generate edges between all pairs of stars
+fn all_pairs(stars: &[(i64, i64)]) -> Vec<Edge> {
+    let mut edges = Vec::new();
+    for i in 0..stars.len() {
+        for j in i + 1..stars.len() {
+            edges.push(Edge {
+                from: i,
+                to: j,
+                weight: (stars[i].0.abs_diff(stars[j].0) + stars[i].1.abs_diff(stars[j].1))
+                    as usize,
+            });
+        }
     }
+    edges
 }
 
 fn main() {
@@ -31,73 +32,39 @@ fn main() {
         for (y, row) in input.lines().enumerate() {
             for (x, c) in row.chars().enumerate() {
                 if c == '*' {
-                    stars.push(Star { x, y });
+                    stars.push((x as i64, y as i64));
                 }
             }
         }
 
-        // find edges between stars (for part 3, only consider edges shorter than 6)
-        let mut edges = vec![Vec::new(); stars.len()];
-        let mut max_dist = 0;
-        for i in 0..stars.len() {
-            for j in i + 1..stars.len() {
-                let d = stars[i].dist(&stars[j]);
-                if part != 3 || d < 6 {
-                    max_dist = max_dist.max(d);
-                    edges[i].push(Edge { dist: d, to: j });
-                    edges[j].push(Edge { dist: d, to: i });
-                }
-            }
-        }
-
-        let mut remaining_stars = (0..stars.len()).collect::<HashSet<_>>();
-        let mut in_constellation = vec![false; stars.len()];
-        let mut shortest_dist = vec![usize::MAX; stars.len()];
-        let mut constellation_sizes = Vec::new();
-
-        while !remaining_stars.is_empty() {
-            // start with any of the remaining stars
-            let start = *remaining_stars.iter().next().unwrap();
-            remaining_stars.remove(&start);
-
-            // perform Prim's algorithm to construct minimum spanning tree
-            let mut bucket_queue = BucketQueue::new(max_dist);
-            bucket_queue.insert(0, start);
-
-            let mut constellation: Vec<usize> = Vec::new();
-            shortest_dist[start] = 0;
-
-            while let Some(s) = bucket_queue.pop() {
-                if in_constellation[s] {
-                    // shortest distance has already been calculated
-                    continue;
-                }
-
-                // add s to constellation and remove it from remaining stars
-                constellation.push(s);
-                remaining_stars.remove(&s);
-                in_constellation[s] = true;
-
-                // for each neighbor of s ...
-                for &e in &edges[s] {
-                    // ... if it's not in a constellation yet and its current
-                    // shortest distance is greater than its distance to s
-                    if !in_constellation[e.to] && shortest_dist[e.to] > e.dist {
-                        // ... update its shortest distance and add it to the queue
-                        shortest_dist[e.to] = e.dist;
-                        bucket_queue.insert(e.dist - 1, e.to);
-                    }
-                }
-            }
-
-            // calculate constellation size
-            let mut sum = 0;
-            for &s in &constellation {
-                sum += shortest_dist[s];
-            }
-            constellation_sizes.push(sum + constellation.len());
+        // the first two parts are small enough to connect all pairs of stars,
+        // but for part 3, we avoid this and only connect stars closer than 6
+        let cutoff = if part == 3 { Some(6) } else { None };
+        let forest = if part == 3 {
+            mst::manhattan(&stars, cutoff)
+        } else {
+            mst::prim(stars.len(), &all_pairs(&stars), cutoff)
+        };
+
+        #[cfg(feature = "benchmark")]
+        benchmark::run(&stars, &all_pairs(&stars), cutoff);
+
+        if cfg!(feature = "visualize") {
+            eprintln!(
+                "Part {part}: {} constellations with {} edges of total length {}",
+                forest.components.len(),
+                forest.edges.len(),
+                forest.weight()
+            );
         }
 
+        // the size of a constellation is the number of its stars plus the
+        // length of all its edges
+        let mut constellation_sizes = forest
+            .components
+            .iter()
+            .map(|c| c.nodes.len() + c.weight)
+            .collect::<Vec<_>>();
         constellation_sizes.sort_unstable();
 
         if part == 3 {
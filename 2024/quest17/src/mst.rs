@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+
+use crate::bucket_queue::IndexedBucketQueue;
+
+/// An undirected weighted edge between two nodes
+#[derive(Clone, Copy, Debug)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub weight: usize,
+}
+
+/// A connected component of a spanning forest
+pub struct Component {
+    pub nodes: Vec<usize>,
+
+    /// The total weight of the component's edges
+    pub weight: usize,
+}
+
+/// A minimum spanning forest. Consists of a minimum spanning tree for each
+/// component of the graph.
+pub struct Forest {
+    pub edges: Vec<Edge>,
+    pub components: Vec<Component>,
+}
+
+impl Forest {
+    /// Group the nodes into components based on the forest's edges
+    fn new(n: usize, edges: Vec<Edge>) -> Self {
+        let mut sets = DisjointSet::new(n);
+        for e in &edges {
+            sets.union(e.from, e.to);
+        }
+
+        let mut index = vec![usize::MAX; n];
+        let mut components = Vec::new();
+        for node in 0..n {
+            let root = sets.find(node);
+            if index[root] == usize::MAX {
+                index[root] = components.len();
+                components.push(Component {
+                    nodes: Vec::new(),
+                    weight: 0,
+                });
+            }
+            components[index[root]].nodes.push(node);
+        }
+        for e in &edges {
+            components[index[sets.find(e.from)]].weight += e.weight;
+        }
+
+        Self { edges, components }
+    }
+
+    /// The total weight of all edges in the forest
+    pub fn weight(&self) -> usize {
+        self.components.iter().map(|c| c.weight).sum()
+    }
+}
+
+/// A union-find data structure with path compression and union by size
+struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Merge the sets containing `a` and `b`. Returns `false` if they were
+    /// already in the same set.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            (a, b) = (b, a);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+}
+
+/// Calculate a minimum spanning forest of a graph with `n` nodes using
+/// Kruskal's algorithm. If a `cutoff` is given, only edges shorter than it
+/// are used.
+pub fn kruskal(n: usize, edges: &[Edge], cutoff: Option<usize>) -> Forest {
+    let mut sorted = edges
+        .iter()
+        .filter(|e| cutoff.is_none_or(|c| e.weight < c))
+        .copied()
+        .collect::<Vec<_>>();
+    sorted.sort_unstable_by_key(|e| e.weight);
+
+    let mut sets = DisjointSet::new(n);
+    let result = sorted
+        .into_iter()
+        .filter(|e| sets.union(e.from, e.to))
+        .collect();
+
+    Forest::new(n, result)
+}
+
+/// Calculate a minimum spanning forest of a graph with `n` nodes using
+/// Prim's algorithm. A tree is grown from each node not covered yet. If a
+/// `cutoff` is given, only edges shorter than it are used.
+pub fn prim(n: usize, edges: &[Edge], cutoff: Option<usize>) -> Forest {
+    let mut neighbors = vec![Vec::new(); n];
+    for &e in edges {
+        if cutoff.is_none_or(|c| e.weight < c) {
+            neighbors[e.from].push((e.to, e.weight));
+            neighbors[e.to].push((e.from, e.weight));
+        }
+    }
+
+    let mut in_tree = vec![false; n];
+    let mut best: Vec<Option<Edge>> = vec![None; n];
+    let mut queue = IndexedBucketQueue::new(n);
+    let mut result = Vec::new();
+
+    for start in 0..n {
+        if in_tree[start] {
+            continue;
+        }
+
+        queue.decrease_key(start, 0);
+        while let Some((_, s)) = queue.pop() {
+            in_tree[s] = true;
+            if let Some(e) = best[s] {
+                result.push(e);
+            }
+
+            for &(to, weight) in &neighbors[s] {
+                if !in_tree[to] && queue.decrease_key(to, weight) {
+                    best[to] = Some(Edge {
+                        from: s,
+                        to,
+                        weight,
+                    });
+                }
+            }
+        }
+    }
+
+    Forest::new(n, result)
+}
+
+/// Calculate a minimum spanning forest of points where the edge weights are
+/// the Manhattan distances between the points. If a `cutoff` is given, only
+/// edges shorter than it are used.
+///
+/// Instead of generating all O(n²) pairs, we perform a sweep in four
+/// directions. In each direction, every point only needs to be connected to
+/// its nearest neighbor in each octant, which gives O(n) candidate edges
+/// that are guaranteed to contain a minimum spanning tree.
+pub fn manhattan(points: &[(i64, i64)], cutoff: Option<usize>) -> Forest {
+    let mut ps = points.to_vec();
+    let mut ids = (0..ps.len()).collect::<Vec<_>>();
+    let mut candidates = Vec::new();
+
+    for k in 0..4 {
+        ids.sort_unstable_by_key(|&i| ps[i].0 + ps[i].1);
+
+        // active points keyed by their negated y coordinate
+        let mut sweep: BTreeMap<i64, usize> = BTreeMap::new();
+        for &i in &ids {
+            let mut done = Vec::new();
+            for (&key, &j) in sweep.range(-ps[i].1..) {
+                let (dx, dy) = (ps[i].0 - ps[j].0, ps[i].1 - ps[j].1);
+                if dy > dx {
+                    break;
+                }
+                candidates.push(Edge {
+                    from: i,
+                    to: j,
+                    weight: (dx + dy) as usize,
+                });
+                done.push(key);
+            }
+            for key in done {
+                sweep.remove(&key);
+            }
+            sweep.insert(-ps[i].1, i);
+        }
+
+        // rotate or mirror the points for the next direction
+        for p in &mut ps {
+            if k % 2 == 1 {
+                p.0 = -p.0;
+            } else {
+                *p = (p.1, p.0);
+            }
+        }
+    }
+
+    kruskal(points.len(), &candidates, cutoff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::all_pairs;
+
+    /// Check that the forest's edges and components agree with each other
+    fn check(n: usize, forest: &Forest) {
+        assert_eq!(forest.edges.len(), n - forest.components.len());
+        assert_eq!(
+            forest.weight(),
+            forest.edges.iter().map(|e| e.weight).sum::<usize>()
+        );
+        assert_eq!(
+            forest
+                .components
+                .iter()
+                .map(|c| c.nodes.len())
+                .sum::<usize>(),
+            n
+        );
+    }
+
+    #[test]
+    fn example() {
+        let stars = [(0, 0), (4, 0), (2, 1), (0, 4), (2, 4)];
+        for forest in [
+            prim(stars.len(), &all_pairs(&stars), None),
+            manhattan(&stars, None),
+        ] {
+            check(stars.len(), &forest);
+            assert_eq!(forest.components.len(), 1);
+            assert_eq!(forest.weight(), 11);
+        }
+    }
+
+    #[test]
+    fn cutoff() {
+        // two clusters that are too far apart to be connected
+        let stars = [(0, 0), (1, 0), (10, 10), (10, 12), (13, 12)];
+        for forest in [
+            prim(stars.len(), &all_pairs(&stars), Some(6)),
+            kruskal(stars.len(), &all_pairs(&stars), Some(6)),
+            manhattan(&stars, Some(6)),
+        ] {
+            check(stars.len(), &forest);
+            let mut weights = forest
+                .components
+                .iter()
+                .map(|c| c.weight)
+                .collect::<Vec<_>>();
+            weights.sort_unstable();
+            assert_eq!(weights, [1, 5]);
+        }
+
+        // a cutoff of 1 leaves all stars unconnected
+        let forest = manhattan(&stars, Some(1));
+        check(stars.len(), &forest);
+        assert_eq!(forest.components.len(), stars.len());
+    }
+
+    #[test]
+    fn manhattan_matches_all_pairs() {
+        for size in [1, 2, 5, 20, 100] {
+            // scatter the stars deterministically, with some duplicates
+            let stars = (0..size)
+                .map(|i| ((i * 17 + 3) % 30, (i * i * 7 + i) % 31))
+                .collect::<Vec<_>>();
+            let edges = all_pairs(&stars);
+            for cutoff in [None, Some(1), Some(6), Some(15)] {
+                let expected = prim(stars.len(), &edges, cutoff);
+                check(stars.len(), &expected);
+                for forest in [
+                    kruskal(stars.len(), &edges, cutoff),
+                    manhattan(&stars, cutoff),
+                ] {
+                    check(stars.len(), &forest);
+                    assert_eq!(forest.weight(), expected.weight());
+                    assert_eq!(forest.components.len(), expected.components.len());
+                }
+            }
+        }
+    }
+}
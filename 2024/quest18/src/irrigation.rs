@@ -0,0 +1,217 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+const DIRS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+/// The best location for a well
+pub struct Well {
+    pub pos: (usize, usize),
+
+    /// The sum of the times the water needs to reach each plant
+    pub total: usize,
+
+    /// The position of each plant and the time the water needs to reach it
+    pub arrivals: Vec<((usize, usize), usize)>,
+}
+
+/// A farm consisting of empty cells (`.`), palm trees (`P`), and walls
+/// (`#`). Cells may also contain a digit from `1` to `9`, which denotes
+/// terrain through which water flows more slowly. Entering such a cell takes
+/// as many time units as the digit says. Entering any other open cell takes
+/// one time unit.
+pub struct Farm {
+    grid: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+impl Farm {
+    pub fn parse(input: &str) -> Self {
+        let lines = input.lines().collect::<Vec<_>>();
+        let width = lines[0].len();
+        let height = lines.len();
+        let grid = lines
+            .into_iter()
+            .flat_map(|l| l.as_bytes().iter().copied())
+            .collect::<Vec<_>>();
+        Self {
+            grid,
+            width,
+            height,
+        }
+    }
+
+    /// The width and height of the farm
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// The time it takes to enter the cell at the given index or `None` if it
+    /// is blocked
+    fn cost(&self, i: usize) -> Option<usize> {
+        match self.grid[i] {
+            b'#' => None,
+            c @ b'1'..=b'9' => Some((c - b'0') as usize),
+            _ => Some(1),
+        }
+    }
+
+    /// Find all cells with the given symbol
+    pub fn find(&self, c: u8) -> Vec<(usize, usize)> {
+        self.grid
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| **g == c)
+            .map(|(i, _)| (i % self.width, i / self.width))
+            .collect()
+    }
+
+    /// Find all empty cells on the border of the farm
+    pub fn entrances(&self) -> Vec<(usize, usize)> {
+        self.find(b'.')
+            .into_iter()
+            .filter(|&(x, y)| x == 0 || y == 0 || x == self.width - 1 || y == self.height - 1)
+            .collect()
+    }
+
+    /// Perform Dijkstra's algorithm from the given sources and write the
+    /// distance to each cell into `dist`. If `reverse` is true, the distances
+    /// are calculated from each cell to the nearest source instead, which
+    /// differs if entering cells has different costs.
+    fn dijkstra(&self, sources: &[(usize, usize)], reverse: bool, dist: &mut [usize]) {
+        dist.fill(usize::MAX);
+        let mut queue = BinaryHeap::new();
+        for &(x, y) in sources {
+            dist[y * self.width + x] = 0;
+            queue.push(Reverse((0, x, y)));
+        }
+
+        while let Some(Reverse((d, x, y))) = queue.pop() {
+            let i = y * self.width + x;
+            if d > dist[i] {
+                continue;
+            }
+            for (dx, dy) in DIRS {
+                let nx = x as i32 + dx;
+                let ny = y as i32 + dy;
+                if nx < 0 || ny < 0 || nx >= self.width as i32 || ny >= self.height as i32 {
+                    continue;
+                }
+                let (nx, ny) = (nx as usize, ny as usize);
+                let ni = ny * self.width + nx;
+                let Some(cost) = self.cost(ni) else {
+                    continue;
+                };
+
+                // in reverse, water flows from the neighbor into this cell
+                let cost = if reverse { self.cost(i).unwrap() } else { cost };
+                if d + cost < dist[ni] {
+                    dist[ni] = d + cost;
+                    queue.push(Reverse((d + cost, nx, ny)));
+                }
+            }
+        }
+    }
+
+    /// Calculate the time water needs to reach each cell if it starts flowing
+    /// from all given sources at once. Unreachable cells get `usize::MAX`.
+    pub fn flood(&self, sources: &[(usize, usize)]) -> Vec<usize> {
+        let mut dist = vec![0; self.grid.len()];
+        self.dijkstra(sources, false, &mut dist);
+        dist
+    }
+
+    /// Calculate, for each cell, the sum of the times water starting in this
+    /// cell needs to reach each of the given targets. Cells from which not
+    /// all targets can be reached get `None`.
+    ///
+    /// Instead of keeping the distances of all targets in memory at the same
+    /// time, we search from one target after the other and accumulate the
+    /// distances, so we only need memory linear in the size of the grid.
+    pub fn total_distances(&self, targets: &[(usize, usize)]) -> Vec<Option<usize>> {
+        let mut totals = vec![Some(0); self.grid.len()];
+        let mut dist = vec![0; self.grid.len()];
+        for &t in targets {
+            self.dijkstra(&[t], true, &mut dist);
+            for (total, &d) in totals.iter_mut().zip(&dist) {
+                *total = total.filter(|_| d != usize::MAX).map(|s| s + d);
+            }
+        }
+        totals
+    }
+
+    /// Find the empty cell for a well from which the water reaches all palm
+    /// trees in the shortest total time. Ties are broken by position (top to
+    /// bottom, then left to right).
+    pub fn best_well(&self) -> Option<Well> {
+        let plants = self.find(b'P');
+        let totals = self.total_distances(&plants);
+        let (i, total) = totals
+            .into_iter()
+            .enumerate()
+            .filter(|&(i, _)| self.grid[i] == b'.')
+            .filter_map(|(i, t)| Some((i, t?)))
+            .min_by_key(|&(i, t)| (t, i))?;
+
+        let pos = (i % self.width, i / self.width);
+        let flooded = self.flood(&[pos]);
+        let arrivals = plants
+            .into_iter()
+            .map(|(x, y)| ((x, y), flooded[y * self.width + x]))
+            .collect();
+
+        Some(Well {
+            pos,
+            total,
+            arrivals,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The time water flowing in from the entrances needs to reach the last
+    /// palm tree
+    fn last_plant(input: &str) -> usize {
+        let farm = Farm::parse(input);
+        let flooded = farm.flood(&farm.entrances());
+        farm.find(b'P')
+            .into_iter()
+            .map(|(x, y)| flooded[y * farm.width + x])
+            .max()
+            .unwrap()
+    }
+
+    /// Find the best well and check that the arrival times add up
+    fn best_well(input: &str) -> Well {
+        let well = Farm::parse(input).best_well().unwrap();
+        assert!(well.arrivals.iter().all(|&(p, _)| p != well.pos));
+        assert_eq!(
+            well.arrivals.iter().map(|&(_, t)| t).sum::<usize>(),
+            well.total
+        );
+        well
+    }
+
+    #[test]
+    fn flooding() {
+        assert_eq!(
+            last_plant("##########\n..#......P\n#.P.####P#\n#.#...P#.#\n##########"),
+            11
+        );
+    }
+
+    #[test]
+    fn wells() {
+        let well = best_well("##########\n#.#......#\n#.P.####P#\n#.#...P#.#\n##########");
+        assert_eq!(well.total, 12);
+
+        // the far palm tree is still reached faster through the slow cell than
+        // around it
+        let well = best_well("#######\n#P.5.P#\n#.###.#\n#.....#\n#######");
+        assert_eq!(well.total, 8);
+        assert_eq!(well.pos, (2, 1));
+    }
+}
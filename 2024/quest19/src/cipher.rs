@@ -0,0 +1,324 @@
+use std::fmt;
+
+/// The cells around a rotation point in clockwise order, starting at the top
+/// left corner
+const RING: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CipherError {
+    EmptyKey,
+    InvalidKey(char),
+    EmptyGrid,
+
+    /// A line of the grid has a different length than the first one
+    Ragged {
+        line: usize,
+    },
+
+    MissingStart,
+    MissingEnd,
+}
+
+impl fmt::Display for CipherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CipherError::EmptyKey => write!(f, "key is empty"),
+            CipherError::InvalidKey(c) => write!(f, "invalid rotation in key: {c}"),
+            CipherError::EmptyGrid => write!(f, "grid is empty"),
+            CipherError::Ragged { line } => {
+                write!(f, "line {line} has a different length than the first line")
+            }
+            CipherError::MissingStart => write!(f, "message has no start marker `>`"),
+            CipherError::MissingEnd => write!(f, "message has no end marker `<`"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Rotation {
+    Left,
+    Right,
+}
+
+/// A sequence of rotations. Keys are applied cyclically to the rotation
+/// points. If a key is longer than the number of rotation points, its
+/// remaining rotations are not used.
+pub struct Key(Vec<Rotation>);
+
+impl Key {
+    /// Parse a key consisting of the letters `L` and `R`
+    pub fn parse(s: &str) -> Result<Self, CipherError> {
+        let rotations = s
+            .trim()
+            .chars()
+            .map(|c| match c {
+                'L' => Ok(Rotation::Left),
+                'R' => Ok(Rotation::Right),
+                _ => Err(CipherError::InvalidKey(c)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if rotations.is_empty() {
+            return Err(CipherError::EmptyKey);
+        }
+        Ok(Self(rotations))
+    }
+}
+
+/// A message found in a grid between the markers `>` and `<`
+pub struct Message {
+    pub text: String,
+}
+
+impl Message {
+    /// Get the message as a number or `None` if it is not one
+    pub fn value(&self) -> Option<u64> {
+        self.text.parse().ok()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid {
+    cells: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+impl Grid {
+    /// Parse a rectangular grid. All lines must have the same length.
+    pub fn parse(input: &str) -> Result<Self, CipherError> {
+        let lines = input.lines().collect::<Vec<_>>();
+        let width = lines.first().ok_or(CipherError::EmptyGrid)?.len();
+        if let Some(line) = lines.iter().position(|l| l.len() != width) {
+            return Err(CipherError::Ragged { line: line + 1 });
+        }
+        let height = lines.len();
+        let cells = lines
+            .into_iter()
+            .flat_map(|l| l.as_bytes().iter().copied())
+            .collect::<Vec<_>>();
+        Ok(Self {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    /// The width and height of the grid
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Extract the message between the first `>` and the following `<`. The
+    /// message is read row by row and may span multiple rows.
+    pub fn message(&self) -> Result<Message, CipherError> {
+        let start = self
+            .cells
+            .iter()
+            .position(|&c| c == b'>')
+            .ok_or(CipherError::MissingStart)?;
+        let len = self.cells[start + 1..]
+            .iter()
+            .position(|&c| c == b'<')
+            .ok_or(CipherError::MissingEnd)?;
+        let text = &self.cells[start + 1..start + 1 + len];
+        Ok(Message {
+            text: String::from_utf8_lossy(text).into_owned(),
+        })
+    }
+}
+
+/// A permutation `p` that moves the item at index `p[i]` to index `i`
+struct Permutation(Vec<usize>);
+
+impl Permutation {
+    fn identity(n: usize) -> Self {
+        Permutation((0..n).collect())
+    }
+
+    fn apply<T: Copy>(&self, items: &[T]) -> Vec<T> {
+        self.0.iter().map(|&i| items[i]).collect()
+    }
+
+    fn inverse(&self) -> Self {
+        let mut result = vec![0; self.0.len()];
+        for (i, &p) in self.0.iter().enumerate() {
+            result[p] = i;
+        }
+        Permutation(result)
+    }
+
+    /// Apply the permutation `exp` times by repeated squaring
+    fn pow(&self, mut exp: u64) -> Self {
+        let mut result = Permutation::identity(self.0.len());
+        let mut base = Permutation(self.0.clone());
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = Permutation(base.apply(&result.0));
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = Permutation(base.apply(&base.0));
+            }
+        }
+        result
+    }
+}
+
+/// A rotation cipher for grids of a fixed size. One round of decryption
+/// rotates the eight cells around each interior cell (the rotation points)
+/// left or right according to the key, row by row. Grids with fewer than
+/// three rows or columns have no rotation points and are left unchanged.
+pub struct Cipher {
+    width: usize,
+    height: usize,
+
+    /// The permutation performed by one round of decryption
+    permutation: Permutation,
+
+    /// The permutation performed by one round of encryption
+    inverse: Permutation,
+}
+
+impl Cipher {
+    pub fn new(key: &Key, width: usize, height: usize) -> Self {
+        let mut cells = (0..width * height).collect::<Vec<_>>();
+        let points = (1..height.saturating_sub(1))
+            .flat_map(|y| (1..width.saturating_sub(1)).map(move |x| (x, y)));
+        for ((x, y), &r) in points.zip(key.0.iter().cycle()) {
+            let ring =
+                RING.map(|(dx, dy)| (y as i32 + dy) as usize * width + (x as i32 + dx) as usize);
+            let mut values = ring.map(|i| cells[i]);
+            match r {
+                Rotation::Left => values.rotate_left(1),
+                Rotation::Right => values.rotate_right(1),
+            }
+            for (i, v) in ring.into_iter().zip(values) {
+                cells[i] = v;
+            }
+        }
+
+        let permutation = Permutation(cells);
+        let inverse = permutation.inverse();
+        Self {
+            width,
+            height,
+            permutation,
+            inverse,
+        }
+    }
+
+    fn check_size(&self, grid: &Grid) {
+        assert!(
+            grid.width == self.width && grid.height == self.height,
+            "Grid size does not match cipher"
+        );
+    }
+
+    /// Decrypt the grid by applying the given number of rounds
+    pub fn decrypt(&self, grid: &Grid, rounds: u64) -> Grid {
+        self.check_size(grid);
+        Grid {
+            cells: self.permutation.pow(rounds).apply(&grid.cells),
+            ..*grid
+        }
+    }
+
+    /// Encrypt the grid by reverting the given number of decryption rounds
+    pub fn encrypt(&self, grid: &Grid, rounds: u64) -> Grid {
+        self.check_size(grid);
+        Grid {
+            cells: self.inverse.pow(rounds).apply(&grid.cells),
+            ..*grid
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encrypt the grid, check that decrypting it again restores the
+    /// original, and return the encrypted grid
+    fn round_trip(key: &str, grid: &str, rounds: u64) -> Grid {
+        let key = Key::parse(key).unwrap();
+        let grid = Grid::parse(grid).unwrap();
+        let (width, height) = grid.size();
+        let cipher = Cipher::new(&key, width, height);
+        let encrypted = cipher.encrypt(&grid, rounds);
+        assert_eq!(cipher.decrypt(&encrypted, rounds), grid);
+        encrypted
+    }
+
+    #[test]
+    fn example() {
+        let grid = Grid::parse(">-IN-\n-----\nW---<").unwrap();
+        let cipher = Cipher::new(&Key::parse("LR").unwrap(), 5, 3);
+        let decrypted = cipher.decrypt(&grid, 1);
+        assert_eq!(decrypted.message().unwrap().text, "WIN");
+        assert_eq!(cipher.encrypt(&decrypted, 1), grid);
+    }
+
+    #[test]
+    fn multiple_rounds() {
+        let plain = "........\n.>1234<.\n..5678..\n........";
+        for rounds in [1, 2, 7, 100, 1048576000] {
+            let encrypted = round_trip("LRRL", plain, rounds);
+            assert_ne!(encrypted, Grid::parse(plain).unwrap());
+        }
+
+        // encrypting for several rounds at once is the same as encrypting
+        // one round after the other
+        let grid = Grid::parse(plain).unwrap();
+        let cipher = Cipher::new(&Key::parse("LRRL").unwrap(), 8, 4);
+        let mut step = grid.clone();
+        for rounds in 1..=10 {
+            step = cipher.encrypt(&step, 1);
+            assert_eq!(cipher.encrypt(&grid, rounds), step);
+        }
+
+        let decrypted = cipher.decrypt(&cipher.encrypt(&grid, 5), 5);
+        assert_eq!(decrypted.message().unwrap().value(), Some(1234));
+    }
+
+    #[test]
+    fn key_lengths() {
+        // more rotations than rotation points
+        round_trip("LRLRLRRRLL", ">12\n3<.\n...", 3);
+
+        // grids without rotation points stay the same
+        let grid = ">12<";
+        assert_eq!(round_trip("R", grid, 5), Grid::parse(grid).unwrap());
+    }
+
+    #[test]
+    fn messages() {
+        let grid = Grid::parse("..>12\n34<..").unwrap();
+        assert_eq!(grid.message().unwrap().value(), Some(1234));
+        let grid = Grid::parse(">AB<").unwrap();
+        assert_eq!(grid.message().unwrap().value(), None);
+        let grid = Grid::parse("..<..>").unwrap();
+        assert_eq!(grid.message().err(), Some(CipherError::MissingEnd));
+        let grid = Grid::parse("12<").unwrap();
+        assert_eq!(grid.message().err(), Some(CipherError::MissingStart));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Key::parse("LX").err(), Some(CipherError::InvalidKey('X')));
+        assert_eq!(Key::parse("").err(), Some(CipherError::EmptyKey));
+        assert_eq!(Grid::parse("").err(), Some(CipherError::EmptyGrid));
+        assert_eq!(
+            Grid::parse("...\n..").err(),
+            Some(CipherError::Ragged { line: 2 })
+        );
+    }
+}
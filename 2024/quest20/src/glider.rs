@@ -0,0 +1,426 @@
+pub const DIRS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+/// The glider initially flies downwards
+const START_DIR: usize = 1;
+
+/// The state of the glider after a number of seconds
+#[derive(Clone, Copy, Debug)]
+pub struct State {
+    pub pos: (usize, usize),
+    pub dir: usize,
+    pub altitude: i64,
+    pub time: usize,
+
+    /// Objective-specific progress (e.g. the number of checkpoints visited)
+    pub stage: usize,
+}
+
+/// Describes what a flight should achieve. The search engine explores all
+/// flights second by second and stops at the first second in which a state
+/// satisfies [`Objective::is_goal`]. Among all such states, the one with the
+/// highest altitude is chosen.
+pub trait Objective {
+    /// The new stage after entering a cell with the given symbol, or `None`
+    /// if the cell must not be entered in the current stage
+    fn advance(&self, stage: usize, cell: u8) -> Option<usize>;
+
+    /// Check if the given state, located on a cell with the given symbol,
+    /// completes the flight
+    fn is_goal(&self, state: &State, cell: u8, start_altitude: i64) -> bool;
+
+    /// If `true`, a state is discarded if the glider has already been at the
+    /// same position, in the same direction and stage, with at least the
+    /// same altitude at an earlier time. This is only valid for objectives
+    /// where arriving earlier is never worse.
+    fn earlier_dominates(&self) -> bool;
+
+    /// The number of seconds after which the search gives up
+    fn max_time(&self) -> usize;
+
+    /// The number of distinct stages
+    fn stages(&self) -> usize;
+}
+
+/// Maximize the altitude after flying for exactly the given number of seconds
+pub struct MaxAltitude {
+    pub time: usize,
+}
+
+impl Objective for MaxAltitude {
+    fn advance(&self, stage: usize, _cell: u8) -> Option<usize> {
+        Some(stage)
+    }
+
+    fn is_goal(&self, state: &State, _cell: u8, _start_altitude: i64) -> bool {
+        state.time == self.time
+    }
+
+    fn earlier_dominates(&self) -> bool {
+        false
+    }
+
+    fn max_time(&self) -> usize {
+        self.time
+    }
+
+    fn stages(&self) -> usize {
+        1
+    }
+}
+
+/// Minimize the time needed to visit the given checkpoints in order and
+/// then return to the start with at least the starting altitude. The start
+/// must not be entered before all checkpoints have been visited.
+pub struct Checkpoints {
+    pub checkpoints: Vec<u8>,
+    pub max_time: usize,
+}
+
+impl Objective for Checkpoints {
+    fn advance(&self, stage: usize, cell: u8) -> Option<usize> {
+        if cell == b'S' {
+            (stage == self.checkpoints.len()).then_some(stage)
+        } else if self.checkpoints.get(stage) == Some(&cell) {
+            Some(stage + 1)
+        } else if self.checkpoints.contains(&cell) {
+            None
+        } else {
+            Some(stage)
+        }
+    }
+
+    fn is_goal(&self, state: &State, cell: u8, start_altitude: i64) -> bool {
+        state.time > 0
+            && cell == b'S'
+            && state.stage == self.checkpoints.len()
+            && state.altitude >= start_altitude
+    }
+
+    fn earlier_dominates(&self) -> bool {
+        true
+    }
+
+    fn max_time(&self) -> usize {
+        self.max_time
+    }
+
+    fn stages(&self) -> usize {
+        self.checkpoints.len() + 1
+    }
+}
+
+/// The result of a successful search
+pub struct Flight {
+    /// The final state
+    pub state: State,
+
+    /// All cells visited, including the start
+    pub route: Vec<(usize, usize)>,
+}
+
+/// A map of air currents. Entering `+` lifts the glider by one, `-` lowers
+/// it by two, and all other cells lower it by one. `#` cannot be entered.
+/// The glider cannot turn around.
+pub struct AirMap {
+    grid: Vec<u8>,
+    width: usize,
+    height: usize,
+    start: (usize, usize),
+}
+
+impl AirMap {
+    pub fn parse(input: &str) -> Self {
+        let lines = input.lines().collect::<Vec<_>>();
+        let width = lines[0].len();
+        let height = lines.len();
+        let grid = lines
+            .into_iter()
+            .flat_map(|l| l.as_bytes().iter().copied())
+            .collect::<Vec<_>>();
+        let start = grid
+            .iter()
+            .position(|&c| c == b'S')
+            .expect("No start found");
+        Self {
+            grid,
+            width,
+            height,
+            start: (start % width, start / width),
+        }
+    }
+
+    /// The change in altitude when entering a cell or `None` if it cannot be
+    /// entered
+    fn lift(cell: u8) -> Option<i64> {
+        match cell {
+            b'#' => None,
+            b'+' => Some(1),
+            b'-' => Some(-2),
+            _ => Some(-1),
+        }
+    }
+
+    /// Search for the best flight starting at `S` with the given altitude.
+    /// Returns `None` if the objective cannot be reached within its time
+    /// limit.
+    ///
+    /// Since every move takes one second, we explore the flights layer by
+    /// layer. In each layer, only the highest state per position, direction
+    /// and stage is kept, so a layer never holds more than
+    /// `4 * width * height * stages` states. The predecessors needed to
+    /// reconstruct the route are kept separately. Whenever their number has
+    /// doubled, we drop those that no longer lead to a state in the current
+    /// layer. The remaining ones can still grow with the length of the
+    /// flight, up to the size of a layer per second in the worst case.
+    pub fn fly(&self, altitude: i64, objective: &impl Objective) -> Option<Flight> {
+        let stages = objective.stages();
+        let index = |s: &State| ((s.pos.1 * self.width + s.pos.0) * 4 + s.dir) * stages + s.stage;
+
+        // all states reached so far together with their predecessors
+        let mut nodes = vec![(
+            State {
+                pos: self.start,
+                dir: START_DIR,
+                altitude,
+                time: 0,
+                stage: 0,
+            },
+            usize::MAX,
+        )];
+        let mut layer = vec![0];
+        let mut kept = nodes.len();
+
+        // the node of each state in the next layer and the highest altitude
+        // each state has been reached with so far
+        let mut in_layer = vec![usize::MAX; self.grid.len() * 4 * stages];
+        let mut earlier = if objective.earlier_dominates() {
+            vec![i64::MIN; self.grid.len() * 4 * stages]
+        } else {
+            Vec::new()
+        };
+
+        for time in 0..=objective.max_time() {
+            let goal = layer
+                .iter()
+                .copied()
+                .filter(|&n| {
+                    let s = &nodes[n].0;
+                    objective.is_goal(s, self.grid[s.pos.1 * self.width + s.pos.0], altitude)
+                })
+                .max_by_key(|&n| nodes[n].0.altitude);
+            if let Some(goal) = goal {
+                return Some(self.reconstruct(&nodes, goal));
+            }
+            if time == objective.max_time() {
+                break;
+            }
+
+            let mut next = Vec::new();
+            for &n in &layer {
+                let s = nodes[n].0;
+                for (dir, (dx, dy)) in DIRS.into_iter().enumerate() {
+                    if (dir + 2) % 4 == s.dir {
+                        continue;
+                    }
+                    let nx = s.pos.0 as i32 + dx;
+                    let ny = s.pos.1 as i32 + dy;
+                    if nx < 0 || ny < 0 || nx >= self.width as i32 || ny >= self.height as i32 {
+                        continue;
+                    }
+                    let pos = (nx as usize, ny as usize);
+                    let cell = self.grid[pos.1 * self.width + pos.0];
+                    let Some(lift) = Self::lift(cell) else {
+                        continue;
+                    };
+                    let Some(stage) = objective.advance(s.stage, cell) else {
+                        continue;
+                    };
+
+                    let state = State {
+                        pos,
+                        dir,
+                        altitude: s.altitude + lift,
+                        time: time + 1,
+                        stage,
+                    };
+                    let key = index(&state);
+                    let other = in_layer[key];
+                    if other != usize::MAX && nodes[other].0.altitude >= state.altitude {
+                        continue;
+                    }
+                    if objective.earlier_dominates() {
+                        if earlier[key] >= state.altitude {
+                            continue;
+                        }
+                        earlier[key] = state.altitude;
+                    }
+
+                    if other != usize::MAX {
+                        // the other node has no successors yet, so we can
+                        // simply replace it
+                        nodes[other] = (state, n);
+                    } else {
+                        in_layer[key] = nodes.len();
+                        next.push(nodes.len());
+                        nodes.push((state, n));
+                    }
+                }
+            }
+
+            if next.is_empty() {
+                break;
+            }
+            for &n in &next {
+                in_layer[index(&nodes[n].0)] = usize::MAX;
+            }
+            layer = next;
+
+            if nodes.len() > 2 * kept {
+                Self::prune(&mut nodes, &mut layer);
+                kept = nodes.len();
+            }
+        }
+
+        None
+    }
+
+    /// Drop all nodes that are not an ancestor of a node in the given layer
+    /// (or in it) and renumber the remaining ones, including the layer.
+    /// Predecessors are always added before their successors, so a single
+    /// forward pass suffices.
+    fn prune(nodes: &mut Vec<(State, usize)>, layer: &mut [usize]) {
+        let mut live = vec![false; nodes.len()];
+        for &n in layer.iter() {
+            let mut n = n;
+            while n != usize::MAX && !live[n] {
+                live[n] = true;
+                n = nodes[n].1;
+            }
+        }
+
+        let mut renumbered = vec![usize::MAX; nodes.len()];
+        let mut len = 0;
+        for i in 0..nodes.len() {
+            if live[i] {
+                let (state, prev) = nodes[i];
+                let prev = if prev == usize::MAX {
+                    prev
+                } else {
+                    renumbered[prev]
+                };
+                nodes[len] = (state, prev);
+                renumbered[i] = len;
+                len += 1;
+            }
+        }
+        nodes.truncate(len);
+
+        for n in layer {
+            *n = renumbered[*n];
+        }
+    }
+
+    /// Follow the predecessors from the given node back to the start
+    fn reconstruct(&self, nodes: &[(State, usize)], goal: usize) -> Flight {
+        let mut route = Vec::new();
+        let mut n = goal;
+        while n != usize::MAX {
+            route.push(nodes[n].0.pos);
+            n = nodes[n].1;
+        }
+        route.reverse();
+        Flight {
+            state: nodes[goal].0,
+            route,
+        }
+    }
+
+    /// The start position
+    pub fn start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    /// The width and height of the map
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Get the symbol at the given position
+    pub fn get(&self, (x, y): (usize, usize)) -> u8 {
+        self.grid[y * self.width + x]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Search for a flight and check that its route is a valid flight that
+    /// ends in its final state
+    fn fly(input: &str, altitude: i64, objective: &impl Objective) -> Flight {
+        let map = AirMap::parse(input);
+        let flight = map.fly(altitude, objective).unwrap();
+        assert_eq!(flight.route.len(), flight.state.time + 1);
+        assert_eq!(flight.route[0], map.start());
+        assert_eq!(flight.route.last(), Some(&flight.state.pos));
+
+        let mut dir = START_DIR;
+        let mut alt = altitude;
+        for w in flight.route.windows(2) {
+            let d = (w[1].0 as i32 - w[0].0 as i32, w[1].1 as i32 - w[0].1 as i32);
+            let next = DIRS.iter().position(|&e| e == d).unwrap();
+            assert_ne!((next + 2) % 4, dir);
+            dir = next;
+            alt += AirMap::lift(map.get(w[1])).unwrap();
+        }
+        assert_eq!(alt, flight.state.altitude);
+        flight
+    }
+
+    #[test]
+    fn max_altitude() {
+        let flight = fly(
+            "#....S....#
+#.........#
+#---------#
+#.........#
+#..+.+.+..#
+#.+-.+.++.#
+#.........#",
+            1000,
+            &MaxAltitude { time: 100 },
+        );
+        assert_eq!(flight.state.altitude, 1045);
+    }
+
+    #[test]
+    fn checkpoints() {
+        let objective = Checkpoints {
+            checkpoints: b"ABC".to_vec(),
+            max_time: 1000,
+        };
+        let flight = fly(
+            "####S####
+#-.+++.-#
+#.+.+.+.#
+#-.+.+.-#
+#A+.-.+C#
+#.+-.-+.#
+#.+.B.+.#
+#########",
+            10000,
+            &objective,
+        );
+        assert_eq!(flight.state.time, 24);
+        assert_eq!(flight.route.first(), flight.route.last());
+    }
+
+    #[test]
+    fn pruning() {
+        // a long flight in a small loop prunes the predecessors many times
+        let map = AirMap::parse("#S..#\n#.#.#\n#...#\n#####");
+        let flight = map.fly(5000, &MaxAltitude { time: 2000 }).unwrap();
+        assert_eq!(flight.route.len(), 2001);
+        assert_eq!(flight.state.altitude, 3000);
+    }
+}
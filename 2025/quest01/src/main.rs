@@ -1,40 +1,33 @@
 use std::fs;
 
+use wheel::{Mode, NameWheel};
+
+mod wheel;
+
 fn main() {
-    for part in [1, 2, 3] {
+    for (part, mode) in [(1, Mode::Clamp), (2, Mode::Wrap), (3, Mode::SwapWithFront)] {
         let input = fs::read_to_string(format!("everybody_codes_e2025_q01_p{}.txt", part))
             .expect("Could not read file");
 
         let (names, instructions) = input.trim().split_once("\n\n").unwrap();
-        let mut names = names.split(",").collect::<Vec<_>>();
-        let instructions = instructions.split(",").collect::<Vec<_>>();
+        let names = names.split(",").collect::<Vec<_>>();
+        let instructions = wheel::parse_instructions(instructions);
 
-        let mut pos: i32 = 0;
-        for i in instructions {
-            let (dir, steps) = i.split_at(1);
-            let steps = steps.parse::<i32>().unwrap();
-            match dir {
-                "L" => pos -= steps,
-                "R" => pos += steps,
-                _ => unreachable!(),
-            }
-
-            match part {
-                1 => {
-                    pos = pos.clamp(0, names.len() as i32 - 1);
-                }
-                2 => {
-                    pos = pos.rem_euclid(names.len() as i32);
-                }
-                3 => {
-                    pos = pos.rem_euclid(names.len() as i32);
-                    names.swap(0, pos as usize);
-                    pos = 0;
-                }
-                _ => unreachable!(),
-            }
+        if cfg!(feature = "visualize") {
+            let mut reflecting = NameWheel::new(names.clone(), Mode::Reflect);
+            reflecting.run(&instructions);
+            eprintln!(
+                "Part {part} with reflecting ends: {}",
+                reflecting.selected()
+            );
         }
 
-        println!("{}", names[pos as usize]);
+        let mut wheel = NameWheel::new(names, mode);
+        if cfg!(feature = "visualize") {
+            eprintln!("Part {part}: {}", wheel.trace(&instructions).join(", "));
+        } else {
+            wheel.run(&instructions);
+        }
+        println!("{}", wheel.selected());
     }
 }
@@ -0,0 +1,186 @@
+/// Describes what happens when the selection moves beyond the ends of the
+/// list of names
+#[derive(Clone, Copy, Debug)]
+pub enum Mode {
+    /// Stop at the first or last name
+    Clamp,
+
+    /// Continue at the other end of the list
+    Wrap,
+
+    /// Wrap around, then swap the selected name with the first one and
+    /// select the first name again
+    SwapWithFront,
+
+    /// Bounce off the ends and walk back the remaining steps
+    Reflect,
+}
+
+/// Parse comma-separated instructions such as `L3,R5` into signed steps
+/// (negative to the left, positive to the right)
+pub fn parse_instructions(s: &str) -> Vec<i64> {
+    s.trim()
+        .split(',')
+        .map(|i| {
+            let (dir, steps) = i.split_at(1);
+            let steps = steps
+                .parse::<i64>()
+                .unwrap_or_else(|e| panic!("Invalid instruction `{i}`: {e}"));
+            match dir {
+                "L" => -steps,
+                "R" => steps,
+                _ => panic!("Invalid direction in instruction `{i}`"),
+            }
+        })
+        .collect()
+}
+
+/// A list of names with a selection that moves according to instructions.
+/// Each instruction takes constant time regardless of the number of names
+/// and the number of steps.
+pub struct NameWheel<'a> {
+    names: Vec<&'a str>,
+    mode: Mode,
+    pos: usize,
+}
+
+impl<'a> NameWheel<'a> {
+    pub fn new(names: Vec<&'a str>, mode: Mode) -> Self {
+        assert!(!names.is_empty(), "List of names must not be empty");
+        Self {
+            names,
+            mode,
+            pos: 0,
+        }
+    }
+
+    /// The currently selected name
+    pub fn selected(&self) -> &'a str {
+        self.names[self.pos]
+    }
+
+    /// Move the selection by the given number of steps and return the newly
+    /// selected name
+    pub fn step(&mut self, steps: i64) -> &'a str {
+        let n = self.names.len() as i64;
+        let target = self.pos as i64 + steps;
+        match self.mode {
+            Mode::Clamp => self.pos = target.clamp(0, n - 1) as usize,
+            Mode::Wrap => self.pos = target.rem_euclid(n) as usize,
+            Mode::SwapWithFront => {
+                self.names.swap(0, target.rem_euclid(n) as usize);
+                self.pos = 0;
+            }
+            Mode::Reflect => {
+                // walking back and forth repeats after 2 * (n - 1) steps
+                let period = 2 * (n - 1);
+                if period > 0 {
+                    let p = target.rem_euclid(period);
+                    self.pos = if p < n { p } else { period - p } as usize;
+                }
+            }
+        }
+        self.selected()
+    }
+
+    /// Apply all instructions
+    pub fn run(&mut self, instructions: &[i64]) {
+        for &s in instructions {
+            self.step(s);
+        }
+    }
+
+    /// Apply all instructions and return the name selected after each one
+    pub fn trace(&mut self, instructions: &[i64]) -> Vec<&'a str> {
+        instructions.iter().map(|&s| self.step(s)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: [&str; 4] = ["Vyrdax", "Drakzyph", "Fyrryn", "Elarzris"];
+
+    fn trace(mode: Mode, instructions: &str) -> Vec<&'static str> {
+        NameWheel::new(NAMES.to_vec(), mode).trace(&parse_instructions(instructions))
+    }
+
+    /// Move one step at a time and bounce off the ends of a list of length `n`
+    fn bounce(mut pos: i64, steps: i64, n: i64) -> i64 {
+        let mut dir = steps.signum();
+        for _ in 0..steps.abs() {
+            if !(0..n).contains(&(pos + dir)) {
+                dir = -dir;
+            }
+            if (0..n).contains(&(pos + dir)) {
+                pos += dir;
+            }
+        }
+        pos
+    }
+
+    #[test]
+    fn examples() {
+        let mut wheel = NameWheel::new(NAMES.to_vec(), Mode::Clamp);
+        wheel.run(&parse_instructions("R3,L2,R3,L1"));
+        assert_eq!(wheel.selected(), "Fyrryn");
+
+        assert_eq!(
+            trace(Mode::Wrap, "R3,L2,R3,L1"),
+            ["Elarzris", "Drakzyph", "Vyrdax", "Elarzris"]
+        );
+        assert_eq!(
+            trace(Mode::SwapWithFront, "R3,L2,R3,L3").last(),
+            Some(&"Drakzyph")
+        );
+    }
+
+    #[test]
+    fn long_steps() {
+        assert_eq!(
+            trace(Mode::Clamp, "L1000000,R1000000"),
+            ["Vyrdax", "Elarzris"]
+        );
+        assert_eq!(
+            trace(Mode::Wrap, "L1000001,R1000000"),
+            ["Elarzris", "Elarzris"]
+        );
+        assert_eq!(trace(Mode::SwapWithFront, "L1000001"), ["Elarzris"]);
+        assert_eq!(
+            trace(Mode::Reflect, "R5,L4,R1000000"),
+            ["Drakzyph", "Elarzris", "Drakzyph"]
+        );
+    }
+
+    #[test]
+    fn single_name() {
+        for mode in [Mode::Clamp, Mode::Wrap, Mode::SwapWithFront, Mode::Reflect] {
+            let mut wheel = NameWheel::new(vec!["Ann"], mode);
+            assert_eq!(wheel.trace(&[3, -7]), ["Ann", "Ann"]);
+        }
+    }
+
+    #[test]
+    fn reflect_matches_bouncing() {
+        for n in 1..=NAMES.len() {
+            let names = &NAMES[..n];
+            for a in -12..=12 {
+                for b in -12..=12 {
+                    let mut pos = 0;
+                    let expected = [a, b].map(|s| {
+                        pos = bounce(pos, s, n as i64);
+                        names[pos as usize]
+                    });
+                    let mut wheel = NameWheel::new(names.to_vec(), Mode::Reflect);
+                    assert_eq!(wheel.trace(&[a, b]), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_instructions("L3,R5\n"), vec![-3, 5]);
+    }
+}
@@ -1,4 +1,8 @@
-use std::fs;
+use std::{cmp::Reverse, fs};
+
+use packing::Packer;
+
+mod packing;
 
 fn parse(file: &str) -> Vec<u64> {
     let input = fs::read_to_string(file).unwrap_or_else(|_| panic!("Unable to read file {file}"));
@@ -10,31 +14,24 @@ fn parse(file: &str) -> Vec<u64> {
 }
 
 fn main() {
+    let packer = Packer {
+        strict: true,
+        max_len: None,
+    };
+
     // part 1
-    let mut numbers = parse("everybody_codes_e2025_q03_p1.txt");
-    numbers.sort();
-    numbers.dedup();
-    println!("{}", numbers.into_iter().sum::<u64>());
+    let numbers = parse("everybody_codes_e2025_q03_p1.txt");
+    println!("{}", packer.largest_set(&numbers).into_iter().sum::<u64>());
 
     // part 2
-    let mut numbers = parse("everybody_codes_e2025_q03_p2.txt");
-    numbers.sort();
-    numbers.dedup();
-    println!("{}", numbers[0..20].iter().sum::<u64>());
+    let numbers = parse("everybody_codes_e2025_q03_p2.txt");
+    let smallest = packer
+        .best_set(&numbers, 20, Reverse)
+        .expect("Not enough distinct crates");
+    println!("{}", smallest.into_iter().sum::<u64>());
 
     // part 3
-    let mut numbers = parse("everybody_codes_e2025_q03_p3.txt");
-    numbers.sort();
-
-    let mut sets: Vec<u64> = Vec::new();
-    for &n in &numbers {
-        let i = sets.partition_point(|&m| m >= n);
-        if i < sets.len() {
-            sets[i] = n;
-        } else {
-            sets.push(n);
-        }
-    }
-
+    let numbers = parse("everybody_codes_e2025_q03_p3.txt");
+    let sets = packer.chains(&numbers);
     println!("{}", sets.len());
 }
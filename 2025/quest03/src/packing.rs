@@ -0,0 +1,142 @@
+/// Packs crates into sets (chains of crates nested into each other)
+pub struct Packer {
+    /// Whether each crate must be strictly smaller than the one it is
+    /// packed into. Otherwise crates of the same size can be packed into
+    /// each other.
+    pub strict: bool,
+
+    /// The maximum number of crates per set
+    pub max_len: Option<usize>,
+}
+
+impl Packer {
+    /// Get the sizes of the crates that may be used together in one set
+    fn candidates(&self, sizes: &[u64]) -> Vec<u64> {
+        let mut result = sizes.to_vec();
+        result.sort_unstable_by(|a, b| b.cmp(a));
+        if self.strict {
+            result.dedup();
+        }
+        result
+    }
+
+    /// Pick `k` crates that can be packed into one set and have the highest
+    /// scores. Returns the sizes in descending order or `None` if there is no
+    /// such set.
+    pub fn best_set<K: Ord>(
+        &self,
+        sizes: &[u64],
+        k: usize,
+        score: impl Fn(u64) -> K,
+    ) -> Option<Vec<u64>> {
+        if self.max_len.is_some_and(|m| k > m) {
+            return None;
+        }
+        let mut candidates = self.candidates(sizes);
+        if candidates.len() < k {
+            return None;
+        }
+
+        // the candidates are sorted by size, so ties are broken in favor of
+        // larger crates
+        candidates.sort_by_key(|&s| std::cmp::Reverse(score(s)));
+        candidates.truncate(k);
+        candidates.sort_unstable_by(|a, b| b.cmp(a));
+        Some(candidates)
+    }
+
+    /// Get the set with the most crates. If there are multiple, the one with
+    /// the largest crates is returned.
+    pub fn largest_set(&self, sizes: &[u64]) -> Vec<u64> {
+        let mut result = self.candidates(sizes);
+        if let Some(m) = self.max_len {
+            result.truncate(m);
+        }
+        result
+    }
+
+    /// Pack all crates into as few sets as possible. Returns the sizes in
+    /// each set in descending order.
+    ///
+    /// Crate sizes are totally ordered, so by Dilworth's theorem the minimum
+    /// number of sets equals the size of the largest group of crates that
+    /// cannot be packed into each other: the highest number of crates with
+    /// the same size for strict ordering, or a single crate otherwise. With
+    /// a maximum set length `m`, at least `ceil(n / m)` sets are needed as
+    /// well. Distributing the crates in descending order round-robin over
+    /// this many sets never puts two crates of the same size into one set
+    /// (for strict ordering) and never exceeds the maximum length.
+    ///
+    /// Panics if the maximum set length is zero.
+    pub fn chains(&self, sizes: &[u64]) -> Vec<Vec<u64>> {
+        assert_ne!(self.max_len, Some(0), "Sets must hold at least one crate");
+        let mut sorted = sizes.to_vec();
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+
+        let antichain = if self.strict {
+            sorted
+                .chunk_by(|a, b| a == b)
+                .map(|c| c.len())
+                .max()
+                .unwrap_or(0)
+        } else {
+            sorted.len().min(1)
+        };
+        let k = match self.max_len {
+            Some(m) => antichain.max(sorted.len().div_ceil(m)),
+            None => antichain,
+        };
+
+        let mut result = vec![Vec::new(); k];
+        for (i, s) in sorted.into_iter().enumerate() {
+            result[i % k].push(s);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [u64; 32] = [
+        4, 51, 13, 64, 57, 51, 82, 57, 16, 88, 89, 48, 32, 49, 49, 2, 84, 65, 49, 43, 9, 13, 2, 3,
+        75, 72, 63, 48, 61, 14, 40, 77,
+    ];
+
+    const STRICT: Packer = Packer {
+        strict: true,
+        max_len: None,
+    };
+
+    #[test]
+    fn sets() {
+        let largest = STRICT.largest_set(&[10, 5, 1, 10, 3, 8, 5, 2, 2]);
+        assert_eq!(largest.into_iter().sum::<u64>(), 29);
+
+        let smallest = STRICT.best_set(&EXAMPLE, 20, std::cmp::Reverse).unwrap();
+        assert_eq!(smallest.into_iter().sum::<u64>(), 781);
+        assert_eq!(STRICT.best_set(&[1, 1, 2], 3, |s| s), None);
+    }
+
+    #[test]
+    fn chains() {
+        let sets = STRICT.chains(&EXAMPLE);
+        assert_eq!(sets.len(), 3);
+        assert!(sets.iter().all(|s| s.windows(2).all(|w| w[0] > w[1])));
+        assert_eq!(sets.iter().map(|s| s.len()).sum::<usize>(), EXAMPLE.len());
+    }
+
+    #[test]
+    fn chains_with_max_len() {
+        // without strict ordering, only the maximum length limits the sets
+        let packer = Packer {
+            strict: false,
+            max_len: Some(5),
+        };
+        let sets = packer.chains(&EXAMPLE);
+        assert_eq!(sets.len(), EXAMPLE.len().div_ceil(5));
+        assert!(sets.iter().all(|s| s.len() <= 5));
+        assert!(sets.iter().all(|s| s.windows(2).all(|w| w[0] >= w[1])));
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt;
+
+use num::rational::Ratio;
+use num::{BigUint, One, Zero};
+
+#[derive(Debug)]
+pub enum GearError {
+    Empty,
+    InvalidGear(String),
+    NoTeeth(usize),
+}
+
+impl fmt::Display for GearError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GearError::Empty => write!(f, "gear train is empty"),
+            GearError::InvalidGear(s) => write!(f, "invalid gear: {s}"),
+            GearError::NoTeeth(i) => write!(f, "gear {i} has no teeth"),
+        }
+    }
+}
+
+/// A gear with a number of teeth on the side of the previous gear and a
+/// number of teeth on the side of the next gear. Plain gears have the same
+/// number on both sides.
+#[derive(Clone, Copy, Debug)]
+pub struct Gear {
+    pub input: u64,
+    pub output: u64,
+}
+
+/// A train of gears, where each gear drives the next one
+pub struct GearTrain {
+    gears: Vec<Gear>,
+}
+
+impl GearTrain {
+    /// Parse one gear per line. A line is either a plain number of teeth or
+    /// two numbers separated by `|` for compound gears (two gears on the
+    /// same axle).
+    pub fn parse(input: &str) -> Result<Self, GearError> {
+        let gears = input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                let (a, b) = line.split_once('|').unwrap_or((line, line));
+                let parse = |s: &str| {
+                    s.trim()
+                        .parse::<u64>()
+                        .map_err(|_| GearError::InvalidGear(line.to_string()))
+                };
+                let gear = Gear {
+                    input: parse(a)?,
+                    output: parse(b)?,
+                };
+                if gear.input == 0 || gear.output == 0 {
+                    return Err(GearError::NoTeeth(i + 1));
+                }
+                Ok(gear)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if gears.is_empty() {
+            return Err(GearError::Empty);
+        }
+        Ok(Self { gears })
+    }
+
+    /// The exact number of turns of the last gear for one turn of the first
+    /// gear as a reduced fraction
+    pub fn ratio(&self) -> Ratio<BigUint> {
+        self.gears.windows(2).fold(Ratio::one(), |acc, w| {
+            acc * Ratio::new(BigUint::from(w[0].output), BigUint::from(w[1].input))
+        })
+    }
+
+    /// The number of full turns the last gear makes if the first gear turns
+    /// the given number of times
+    pub fn turns_of_last(&self, turns: u64) -> BigUint {
+        (self.ratio() * BigUint::from(turns)).floor().to_integer()
+    }
+
+    /// The number of times the first gear has to turn so that the last gear
+    /// makes at least the given number of turns
+    pub fn turns_of_first(&self, turns: u64) -> BigUint {
+        let ratio = self.ratio();
+        debug_assert!(!ratio.is_zero());
+        (Ratio::from_integer(BigUint::from(turns)) / ratio)
+            .ceil()
+            .to_integer()
+    }
+}
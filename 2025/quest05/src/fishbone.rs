@@ -0,0 +1,242 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ParseError {
+    MissingId,
+    InvalidNumber(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingId => write!(f, "missing identifier"),
+            ParseError::InvalidNumber(s) => write!(f, "invalid number: {s}"),
+        }
+    }
+}
+
+/// A non-negative integer of arbitrary length, stored as decimal digits
+/// without leading zeros (most significant digit first)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Digits(Vec<u8>);
+
+impl Digits {
+    /// Concatenate the decimal representations of the given numbers
+    fn concat(numbers: impl IntoIterator<Item = u64>) -> Self {
+        let mut digits = Vec::new();
+        for n in numbers {
+            digits.extend(n.to_string().bytes().map(|b| b - b'0'));
+        }
+        let zeros = digits.iter().take_while(|&&d| d == 0).count();
+        digits.drain(..zeros);
+        Digits(digits)
+    }
+
+    /// Subtract a number that is not larger than this one
+    pub fn difference(&self, other: &Digits) -> Digits {
+        assert!(self >= other, "Difference would be negative");
+        let mut result = self.0.clone();
+        let mut borrow = 0;
+        for i in 0..result.len() {
+            let j = result.len() - 1 - i;
+            let sub = other.0.len().checked_sub(1 + i).map_or(0, |k| other.0[k]) + borrow;
+            if result[j] < sub {
+                result[j] += 10 - sub;
+                borrow = 1;
+            } else {
+                result[j] -= sub;
+                borrow = 0;
+            }
+        }
+        let zeros = result.iter().take_while(|&&d| d == 0).count();
+        result.drain(..zeros);
+        Digits(result)
+    }
+}
+
+impl Ord for Digits {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // without leading zeros, longer numbers are larger
+        self.0
+            .len()
+            .cmp(&other.0.len())
+            .then_with(|| self.0.cmp(&other.0))
+    }
+}
+
+impl PartialOrd for Digits {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Digits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "0");
+        }
+        for d in &self.0 {
+            write!(f, "{d}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A segment of the spine with optional numbers on its left and right
+#[derive(Clone, Copy, Debug)]
+pub struct Segment {
+    pub left: Option<u64>,
+    pub center: u64,
+    pub right: Option<u64>,
+}
+
+impl Segment {
+    /// The number formed by the segment's numbers from left to right
+    pub fn level(&self) -> Digits {
+        Digits::concat(self.left.into_iter().chain([self.center]).chain(self.right))
+    }
+}
+
+/// A sword built from a list of numbers. Each number is placed on the left
+/// of the first spine segment whose center is larger and whose left side is
+/// free, or on the right of the first one whose center is smaller and whose
+/// right side is free. If there is no such segment, a new one is appended.
+pub struct Fishbone {
+    pub id: u64,
+    spine: Vec<Segment>,
+
+    /// The quality is needed for every comparison, so it is computed once
+    quality: Digits,
+}
+
+impl Fishbone {
+    /// Parse a line of the form `id:n1,n2,...`
+    pub fn parse(line: &str) -> Result<Self, ParseError> {
+        let parse = |s: &str| {
+            s.trim()
+                .parse::<u64>()
+                .map_err(|_| ParseError::InvalidNumber(s.to_string()))
+        };
+        let (id, numbers) = line.split_once(':').ok_or(ParseError::MissingId)?;
+        let id = parse(id)?;
+
+        let mut spine: Vec<Segment> = Vec::new();
+        for n in numbers.split(',') {
+            let n = parse(n)?;
+            let free = spine.iter_mut().find(|s| {
+                (n < s.center && s.left.is_none()) || (n > s.center && s.right.is_none())
+            });
+            match free {
+                Some(s) if n < s.center => s.left = Some(n),
+                Some(s) => s.right = Some(n),
+                None => spine.push(Segment {
+                    left: None,
+                    center: n,
+                    right: None,
+                }),
+            }
+        }
+
+        let quality = Digits::concat(spine.iter().map(|s| s.center));
+        Ok(Self { id, spine, quality })
+    }
+
+    /// The number formed by the centers of all spine segments
+    pub fn quality(&self) -> &Digits {
+        &self.quality
+    }
+
+    /// The numbers formed by each segment of the spine
+    pub fn levels(&self) -> Vec<Digits> {
+        self.spine.iter().map(|s| s.level()).collect()
+    }
+
+    /// Compare two swords: first by quality, then level by level from the
+    /// top, and finally by identifier. The levels are only built if the
+    /// qualities are equal.
+    pub fn compare(&self, other: &Self) -> Ordering {
+        self.quality
+            .cmp(&other.quality)
+            .then_with(|| {
+                for (a, b) in self.levels().into_iter().zip(other.levels()) {
+                    match a.cmp(&b) {
+                        Ordering::Equal => {}
+                        o => return o,
+                    }
+                }
+                // a sword whose levels all match the top of a longer sword
+                // is the weaker one
+                self.spine.len().cmp(&other.spine.len())
+            })
+            .then_with(|| self.id.cmp(&other.id))
+    }
+
+    /// Render the fishbone as ASCII art with the spine in a vertical line
+    pub fn render(&self) -> String {
+        let left_width = self
+            .spine
+            .iter()
+            .filter_map(|s| s.left.map(|l| l.to_string().len()))
+            .max()
+            .unwrap_or(0);
+        let center_width = self
+            .spine
+            .iter()
+            .map(|s| s.center.to_string().len())
+            .max()
+            .unwrap_or(0);
+
+        let mut lines = Vec::new();
+        for (i, s) in self.spine.iter().enumerate() {
+            if i > 0 {
+                lines.push(format!(
+                    "{:w$} {:^cw$}",
+                    "",
+                    "|",
+                    w = left_width,
+                    cw = center_width
+                ));
+            }
+            let left = s
+                .left
+                .map_or(" ".repeat(left_width + 1), |l| format!("{l:>left_width$}-"));
+            let right = s.right.map_or(String::new(), |r| format!("-{r}"));
+            lines.push(format!(
+                "{left}{:^center_width$}{right}",
+                s.center.to_string()
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render() {
+        let sword = Fishbone::parse("1:5,3,7,4").unwrap();
+        assert_eq!(sword.render(), "3-5-7\n  |\n  4");
+        let sword = Fishbone::parse("2:10,12,2,5").unwrap();
+        assert_eq!(sword.render(), "2-10-12\n  | \n  5 ");
+    }
+
+    #[test]
+    fn compare_breaks_ties() {
+        let sword = |s| Fishbone::parse(s).unwrap();
+
+        // same quality, but the level `456` beats `56`
+        assert_eq!(sword("1:5,4,6").compare(&sword("2:5,6")), Ordering::Greater);
+
+        // same quality and levels, so the identifier decides
+        assert_eq!(
+            sword("3:5,4,6").compare(&sword("1:5,4,6")),
+            Ordering::Greater
+        );
+
+        // the quality goes first
+        assert_eq!(sword("1:5,4,6").compare(&sword("2:7")), Ordering::Less);
+    }
+}
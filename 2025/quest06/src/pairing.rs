@@ -0,0 +1,201 @@
+/// The positions, relative to a novice, at which mentors are accepted. A
+/// mentor at distance `behind` before the novice or `ahead` after it is
+/// still accepted.
+#[derive(Clone, Copy, Debug)]
+pub struct Window {
+    pub behind: u64,
+    pub ahead: u64,
+}
+
+impl Window {
+    /// Accept all mentors before the novice
+    pub fn before() -> Self {
+        Self {
+            behind: u64::MAX,
+            ahead: 0,
+        }
+    }
+
+    /// Accept all mentors at most `distance` positions away from the novice
+    pub fn within(distance: u64) -> Self {
+        Self {
+            behind: distance,
+            ahead: distance,
+        }
+    }
+}
+
+/// A row of people given by a pattern of professions that is repeated a
+/// number of times. Lowercase letters are novices and uppercase letters are
+/// mentors of the same profession.
+pub struct Row<'a> {
+    pattern: &'a [u8],
+    repetitions: u64,
+}
+
+impl<'a> Row<'a> {
+    pub fn new(pattern: &'a str, repetitions: u64) -> Self {
+        Self {
+            pattern: pattern.as_bytes(),
+            repetitions,
+        }
+    }
+
+    /// Count all pairs of a novice and a mentor of one of the given
+    /// professions (identified by their lowercase letter) within the window
+    pub fn pairs(&self, professions: &[u8], window: Window) -> u64 {
+        professions
+            .iter()
+            .map(|&p| self.pairs_of(p, p.to_ascii_uppercase(), window))
+            .sum()
+    }
+
+    /// Count all pairs of the given novice and mentor within the window.
+    ///
+    /// Let `C(x)` be the number of mentors before position `x` of the whole
+    /// row. Since `C(x + n) = C(x) + m`, where `n` is the length of the
+    /// pattern and `m` the number of mentors in it, `C` can be evaluated in
+    /// constant time with prefix counts over the pattern. The mentors of a
+    /// novice at position `k * n + i` are `C(hi) - C(lo)` with window bounds
+    /// `hi` and `lo` that grow linearly with `k`, so the sums over all
+    /// repetitions have a closed form. This takes O(n) time regardless of
+    /// the number of repetitions and the size of the window.
+    pub fn pairs_of(&self, novice: u8, mentor: u8, window: Window) -> u64 {
+        let n = self.pattern.len() as i128;
+        let r = self.repetitions as i128;
+        if n == 0 || r == 0 {
+            return 0;
+        }
+        let len = n * r;
+
+        let mut prefix = vec![0; self.pattern.len() + 1];
+        for (i, &c) in self.pattern.iter().enumerate() {
+            prefix[i + 1] = prefix[i] + (c == mentor) as i128;
+        }
+        let m = prefix[self.pattern.len()];
+
+        // sum of C(k * n + start) for all k in `from..to`, where all
+        // arguments must lie within the row
+        let sum = |start: i128, from: i128, to: i128| {
+            let count = (to - from).max(0);
+            let q = start.div_euclid(n);
+            let rem = start.rem_euclid(n) as usize;
+            count * prefix[rem] + m * (count * q + (from + to - 1) * count / 2)
+        };
+
+        let mut total = 0;
+        for (i, _) in self
+            .pattern
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == novice)
+        {
+            let i = i as i128;
+
+            // upper bounds beyond the end of the row are clamped to it
+            let hi = i + window.ahead as i128 + 1;
+            let clamped = if hi > len {
+                0
+            } else {
+                ((len - hi) / n + 1).min(r)
+            };
+            total += sum(hi, 0, clamped) + (r - clamped) * r * m;
+
+            // lower bounds before the start of the row are clamped to 0
+            let lo = i - window.behind as i128;
+            let clamped = if lo >= 0 {
+                0
+            } else {
+                ((-lo + n - 1) / n).min(r)
+            };
+            total -= sum(lo, clamped, r);
+        }
+
+        total as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Count the pairs by expanding the row and checking every mentor of
+    /// every novice of the given professions
+    fn brute_force(pattern: &str, repetitions: usize, professions: &[u8], window: Window) -> u64 {
+        let row = pattern.repeat(repetitions).into_bytes();
+        let mut total = 0;
+        for (i, &c) in row.iter().enumerate() {
+            if !professions.contains(&c) {
+                continue;
+            }
+            for (j, &d) in row.iter().enumerate() {
+                let accepted = if j < i {
+                    (i - j) as u64 <= window.behind
+                } else {
+                    (j - i) as u64 <= window.ahead
+                };
+                total += (accepted && d == c.to_ascii_uppercase()) as u64;
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn examples() {
+        let row = Row::new("ABabACacBCbca", 1);
+        assert_eq!(row.pairs(b"a", Window::before()), 5);
+        assert_eq!(row.pairs(b"abc", Window::before()), 11);
+
+        let pattern = "AABCBABCABCabcabcABCCBAACBCa";
+        assert_eq!(Row::new(pattern, 1).pairs(b"abc", Window::within(10)), 34);
+        assert_eq!(Row::new(pattern, 2).pairs(b"abc", Window::within(10)), 72);
+        assert_eq!(
+            Row::new(pattern, 1000).pairs(b"abc", Window::within(1000)),
+            3442321
+        );
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let patterns = [
+            "",
+            "a",
+            "A",
+            "aA",
+            "Aa",
+            "AaB",
+            "abAB",
+            "BbAaCcaAbB",
+            "aaaA",
+        ];
+        for pattern in patterns {
+            let n = pattern.len() as u64;
+            let windows = [
+                Window::before(),
+                Window::within(0),
+                Window::within(1),
+                Window::within(n),
+                Window::within(n + 1),
+                Window::within(2 * n + 3),
+                Window::within(1000),
+                Window {
+                    behind: 0,
+                    ahead: n + 2,
+                },
+                Window {
+                    behind: 3 * n,
+                    ahead: 1,
+                },
+            ];
+            for repetitions in 0..=6 {
+                for window in windows {
+                    assert_eq!(
+                        Row::new(pattern, repetitions as u64).pairs(b"ab", window),
+                        brute_force(pattern, repetitions, b"ab", window),
+                        "{pattern} * {repetitions} with {window:?}"
+                    );
+                }
+            }
+        }
+    }
+}
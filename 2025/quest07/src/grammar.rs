@@ -0,0 +1,364 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+#[derive(Debug)]
+pub enum GrammarError {
+    /// A rule does not contain ` > `
+    MissingArrow(String),
+
+    /// A rule contains an empty symbol
+    EmptySymbol(String),
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrammarError::MissingArrow(r) => write!(f, "rule has no ` > `: {r}"),
+            GrammarError::EmptySymbol(r) => write!(f, "rule contains an empty symbol: {r}"),
+        }
+    }
+}
+
+/// Marks a missing transition
+const NONE: usize = usize::MAX;
+
+/// A set of rules of the form `X > a,b,c` saying which symbols may follow
+/// a symbol. Names are sequences of symbols that obey the rules. Symbols may
+/// consist of multiple characters, so a name can sometimes be split into
+/// symbols in different ways.
+///
+/// The rules are compiled into a deterministic automaton over single
+/// characters, whose states are the sets of positions within symbols that
+/// the characters read so far could end at. This way, every name
+/// corresponds to exactly one path, so names can be counted and enumerated
+/// without duplicates.
+pub struct Grammar {
+    /// All characters that occur in symbols
+    alphabet: Vec<char>,
+
+    /// The next state for each state and character of the alphabet or
+    /// `NONE`. State 0 is the initial state.
+    transitions: Vec<Vec<usize>>,
+
+    /// States in which the characters read so far form a complete name
+    accepting: Vec<bool>,
+}
+
+impl Grammar {
+    /// Parse rules, one per line
+    pub fn parse(rules: &str) -> Result<Self, GrammarError> {
+        let mut symbols: Vec<Vec<char>> = Vec::new();
+        let mut ids = HashMap::new();
+        let mut intern = |s: &str| -> usize {
+            *ids.entry(s.to_string()).or_insert_with(|| {
+                symbols.push(s.chars().collect());
+                symbols.len() - 1
+            })
+        };
+
+        let mut successors = Vec::new();
+        for r in rules.lines().filter(|l| !l.trim().is_empty()) {
+            let (left, right) = r
+                .split_once(" > ")
+                .ok_or_else(|| GrammarError::MissingArrow(r.to_string()))?;
+            let mut side = vec![left.trim()];
+            side.extend(right.split(',').map(str::trim));
+            if side.iter().any(|s| s.is_empty()) {
+                return Err(GrammarError::EmptySymbol(r.to_string()));
+            }
+            let side = side.into_iter().map(&mut intern).collect::<Vec<_>>();
+            successors.push((side[0], side[1..].to_vec()));
+        }
+
+        let mut next = vec![Vec::new(); symbols.len()];
+        for (s, succ) in successors {
+            next[s].extend(succ);
+        }
+
+        Ok(Self::compile(&symbols, &next))
+    }
+
+    /// Build the automaton with the subset construction. A position is a
+    /// symbol together with the number of its characters read so far
+    /// (at least 1). The initial state is the empty set of positions.
+    fn compile(symbols: &[Vec<char>], next: &[Vec<usize>]) -> Self {
+        let mut alphabet = symbols.iter().flatten().copied().collect::<Vec<_>>();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+
+        let step = |set: &[(usize, usize)], c: char| {
+            let mut result = Vec::new();
+            if set.is_empty() {
+                // any symbol may start a name
+                result.extend(
+                    (0..symbols.len())
+                        .filter(|&t| symbols[t][0] == c)
+                        .map(|t| (t, 1)),
+                );
+            }
+            for &(s, read) in set {
+                if read < symbols[s].len() {
+                    if symbols[s][read] == c {
+                        result.push((s, read + 1));
+                    }
+                } else {
+                    result.extend(
+                        next[s]
+                            .iter()
+                            .filter(|&&t| symbols[t][0] == c)
+                            .map(|&t| (t, 1)),
+                    );
+                }
+            }
+            result.sort_unstable();
+            result.dedup();
+            result
+        };
+
+        let mut states = vec![Vec::new()];
+        let mut ids = HashMap::from([(Vec::new(), 0)]);
+        let mut transitions = Vec::new();
+        let mut i = 0;
+        while i < states.len() {
+            let mut row = vec![NONE; alphabet.len()];
+            for (ci, &c) in alphabet.iter().enumerate() {
+                let set = step(&states[i], c);
+                if set.is_empty() {
+                    continue;
+                }
+                row[ci] = *ids.entry(set.clone()).or_insert_with(|| {
+                    states.push(set);
+                    states.len() - 1
+                });
+            }
+            transitions.push(row);
+            i += 1;
+        }
+
+        let accepting = states
+            .iter()
+            .map(|set| set.iter().any(|&(s, read)| read == symbols[s].len()))
+            .collect();
+
+        Self {
+            alphabet,
+            transitions,
+            accepting,
+        }
+    }
+
+    /// Read the given characters and return the state the automaton ends up
+    /// in or `None` if no name starts with them
+    fn run(&self, text: &str) -> Option<usize> {
+        text.chars().try_fold(0, |state, c| {
+            let ci = self.alphabet.binary_search(&c).ok()?;
+            let next = self.transitions[state][ci];
+            (next != NONE).then_some(next)
+        })
+    }
+
+    /// Check if the given name obeys the rules
+    pub fn accepts(&self, name: &str) -> bool {
+        !name.is_empty() && self.run(name).is_some_and(|s| self.accepting[s])
+    }
+
+    /// Count the distinct names that start with one of the given prefixes
+    /// and whose length (in characters) is in the given range. Prefixes
+    /// that start with another prefix are skipped, since their names have
+    /// already been counted.
+    pub fn count_names(&self, prefixes: &[&str], lengths: RangeInclusive<usize>) -> u64 {
+        let mut viable = prefixes
+            .iter()
+            .filter_map(|p| Some((*p, self.run(p)?)))
+            .collect::<Vec<_>>();
+        viable.sort_unstable();
+        viable.dedup();
+
+        let mut cache = vec![vec![None; lengths.end() + 1]; self.transitions.len()];
+        let mut total = 0;
+        for &(prefix, state) in &viable {
+            if viable
+                .iter()
+                .any(|&(other, _)| other != prefix && prefix.starts_with(other))
+            {
+                continue;
+            }
+            let len = prefix.chars().count();
+            if len <= *lengths.end() {
+                total += self.count_from(state, len, &lengths, &mut cache);
+            }
+        }
+        total
+    }
+
+    /// Count the names that can be completed from the given state if `len`
+    /// characters have been read so far
+    fn count_from(
+        &self,
+        state: usize,
+        len: usize,
+        lengths: &RangeInclusive<usize>,
+        cache: &mut [Vec<Option<u64>>],
+    ) -> u64 {
+        if let Some(c) = cache[state][len] {
+            return c;
+        }
+        let mut result = (self.accepting[state] && lengths.contains(&len)) as u64;
+        if len < *lengths.end() {
+            for &next in &self.transitions[state] {
+                if next != NONE {
+                    result += self.count_from(next, len + 1, lengths, cache);
+                }
+            }
+        }
+        cache[state][len] = Some(result);
+        result
+    }
+
+    /// Lazily enumerate all names that start with the given prefix and whose
+    /// length is in the given range, in lexicographical order
+    pub fn names(&self, prefix: &str, lengths: RangeInclusive<usize>) -> Names<'_> {
+        let stack = self
+            .run(prefix)
+            .map(|state| (state, prefix.to_string()))
+            .into_iter()
+            .filter(|(_, p)| p.chars().count() <= *lengths.end())
+            .collect();
+        Names {
+            grammar: self,
+            stack,
+            lengths,
+        }
+    }
+}
+
+/// An iterator over names generated by a [`Grammar`]
+pub struct Names<'a> {
+    grammar: &'a Grammar,
+    stack: Vec<(usize, String)>,
+    lengths: RangeInclusive<usize>,
+}
+
+impl Iterator for Names<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        while let Some((state, name)) = self.stack.pop() {
+            let len = name.chars().count();
+            if len < *self.lengths.end() {
+                // push in reverse so that names are visited in order
+                for (ci, &next) in self.grammar.transitions[state].iter().enumerate().rev() {
+                    if next != NONE {
+                        let mut n = name.clone();
+                        n.push(self.grammar.alphabet[ci]);
+                        self.stack.push((next, n));
+                    }
+                }
+            }
+            if self.grammar.accepting[state] && self.lengths.contains(&len) {
+                return Some(name);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Enumerate all names with the given prefix and check them against the
+    /// count and the rules
+    fn names(grammar: &Grammar, prefix: &str, lengths: RangeInclusive<usize>) -> Vec<String> {
+        let names = grammar.names(prefix, lengths.clone()).collect::<Vec<_>>();
+        assert_eq!(names.len() as u64, grammar.count_names(&[prefix], lengths));
+        assert!(
+            names
+                .iter()
+                .all(|n| n.starts_with(prefix) && grammar.accepts(n))
+        );
+        assert!(names.is_sorted());
+        names
+    }
+
+    #[test]
+    fn example() {
+        let grammar = Grammar::parse(
+            "r > a,i,o
+i > p,w
+n > e,r
+o > n,m
+k > f,r
+a > k
+U > r
+e > t
+O > r
+t > h",
+        )
+        .unwrap();
+        let accepted = ["Oronris", "Urakris", "Oroneth", "Uraketh"].map(|n| grammar.accepts(n));
+        assert_eq!(accepted, [false, false, true, false]);
+
+        let grammar = Grammar::parse(
+            "X > a,o
+a > r,t
+r > y,e,a
+h > a,e,v
+t > h
+v > e
+y > p,t",
+        )
+        .unwrap();
+        assert_eq!(names(&grammar, "Xaryt", 7..=11).len(), 25);
+    }
+
+    #[test]
+    fn multi_character_symbols() {
+        // "Kabc" can be split as Ka,b,c or as Ka,bc but is only one name
+        let grammar = Grammar::parse("Ka > b,bc\nb > c\nbc > Ka\nc > Ka").unwrap();
+        assert_eq!(
+            names(&grammar, "Ka", 1..=6),
+            ["Ka", "Kab", "Kabc", "KabcKa"]
+        );
+        assert_eq!(names(&grammar, "K", 3..=4), ["Kab", "Kabc"]);
+        assert_eq!(names(&grammar, "b", 1..=4), ["b", "bc", "bcKa"]);
+        assert!(names(&grammar, "cb", 1..=6).is_empty());
+        assert!(!grammar.accepts("K"));
+        assert!(!grammar.accepts("Kac"));
+    }
+
+    #[test]
+    fn prefix_dedup() {
+        let grammar = Grammar::parse("Ka > b,bc\nb > c\nbc > Ka\nc > Ka").unwrap();
+
+        // names starting with "Kab" were already counted for "Ka", and "x"
+        // does not start any name
+        assert_eq!(grammar.count_names(&["Kab", "Ka", "x", "Ka"], 1..=6), 4);
+        assert_eq!(grammar.count_names(&["Kabc", "Kab"], 1..=6), 3);
+    }
+
+    #[test]
+    fn lazy_order() {
+        // far too many names to enumerate them all
+        let grammar = Grammar::parse("a > b\nb > a,b").unwrap();
+        assert!(grammar.count_names(&["a"], 1..=80) > 1 << 50);
+        let first = grammar.names("a", 1..=80).take(4).collect::<Vec<_>>();
+        assert_eq!(first, ["a", "ab", "aba", "abab"]);
+
+        let first = grammar.names("ab", 3..=80).take(3).collect::<Vec<_>>();
+        assert_eq!(first, ["aba", "abab", "ababa"]);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            Grammar::parse("a -> b"),
+            Err(GrammarError::MissingArrow(_))
+        ));
+        assert!(matches!(
+            Grammar::parse("a > b,"),
+            Err(GrammarError::EmptySymbol(_))
+        ));
+    }
+}
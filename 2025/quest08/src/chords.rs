@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+
+/// A thread (or a cut) between two nails, numbered from 1, with `a < b`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Chord {
+    pub a: usize,
+    pub b: usize,
+}
+
+impl Chord {
+    pub fn new(x: usize, y: usize) -> Self {
+        assert_ne!(x, y, "A chord needs two different nails");
+        Self {
+            a: x.min(y),
+            b: x.max(y),
+        }
+    }
+
+    /// Check if the two chords cross strictly inside the circle. Chords that
+    /// only share a nail do not cross.
+    pub fn crosses(&self, other: &Chord) -> bool {
+        (self.a < other.a && other.a < self.b && self.b < other.b)
+            || (other.a < self.a && self.a < other.b && other.b < self.b)
+    }
+
+    /// Check if the chord would cut the other one, i.e. if they cross or are
+    /// the same
+    pub fn cuts(&self, other: &Chord) -> bool {
+        self == other || self.crosses(other)
+    }
+}
+
+/// A Fenwick tree over the indexes `1..=n` for prefix sums
+struct Fenwick(Vec<u64>);
+
+impl Fenwick {
+    fn new(n: usize) -> Self {
+        Fenwick(vec![0; n + 1])
+    }
+
+    fn add(&mut self, mut i: usize, v: u64) {
+        while i < self.0.len() {
+            self.0[i] += v;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// The sum of the values at the indexes `1..=i`
+    fn sum(&self, mut i: usize) -> u64 {
+        let mut result = 0;
+        while i > 0 {
+            result += self.0[i];
+            i -= i & i.wrapping_neg();
+        }
+        result
+    }
+}
+
+/// Threads spanned between `nails` nails that are evenly distributed on a
+/// circle
+pub struct StringArt {
+    nails: usize,
+    threads: Vec<Chord>,
+}
+
+impl StringArt {
+    /// Create the threads by following the given sequence of nails
+    pub fn new(nails: usize, sequence: &[usize]) -> Self {
+        assert!(
+            sequence.iter().all(|n| (1..=nails).contains(n)),
+            "Nail out of range"
+        );
+        let threads = sequence
+            .windows(2)
+            .map(|w| Chord::new(w[0], w[1]))
+            .collect();
+        Self { nails, threads }
+    }
+
+    /// Count the threads that pass through the center of the circle
+    pub fn diameters(&self) -> usize {
+        if !self.nails.is_multiple_of(2) {
+            return 0;
+        }
+        self.threads
+            .iter()
+            .filter(|t| t.b - t.a == self.nails / 2)
+            .count()
+    }
+
+    /// Count the pairs of threads that cross or are the same.
+    ///
+    /// We visit the threads ordered by their first nail. Every earlier
+    /// thread whose second nail lies strictly between the nails of the
+    /// current thread crosses it. The second nails of the visited threads
+    /// are kept in a Fenwick tree, so this takes O(t log n) time for `t`
+    /// threads.
+    pub fn crossings(&self) -> u64 {
+        let mut sorted = self.threads.clone();
+        sorted.sort_unstable();
+
+        let mut tree = Fenwick::new(self.nails);
+        let mut result = 0;
+        for group in sorted.chunk_by(|x, y| x.a == y.a) {
+            // threads with the same first nail do not cross
+            for t in group {
+                result += tree.sum(t.b - 1) - tree.sum(t.a);
+            }
+            for t in group {
+                tree.add(t.b, 1);
+            }
+        }
+
+        let mut same = HashMap::new();
+        for t in &self.threads {
+            *same.entry(*t).or_insert(0u64) += 1;
+        }
+        result + same.values().map(|&k| k * (k - 1) / 2).sum::<u64>()
+    }
+
+    /// Find the cut through two nails that cuts the most threads. Returns
+    /// the first such cut (ordered by nails) and the number of threads.
+    ///
+    /// A thread `(a, b)` is cut by all cuts `(x, y)` with `a < x < b < y`
+    /// or `x < a < y < b`, which are two rectangles in the plane of all
+    /// cuts, plus the cut `(a, b)` itself. We add up all rectangles in a 2D
+    /// difference array, so this takes O(n² + t) time.
+    pub fn best_cut(&self) -> Option<(Chord, usize)> {
+        let n = self.nails;
+        let size = n + 2;
+        let mut grid = vec![0i64; size * size];
+        let mut add = |x1: usize, x2: usize, y1: usize, y2: usize| {
+            if x1 > x2 || y1 > y2 {
+                return;
+            }
+            grid[y1 * size + x1] += 1;
+            grid[y1 * size + x2 + 1] -= 1;
+            grid[(y2 + 1) * size + x1] -= 1;
+            grid[(y2 + 1) * size + x2 + 1] += 1;
+        };
+        for t in &self.threads {
+            add(t.a + 1, t.b - 1, t.b + 1, n);
+            add(1, t.a - 1, t.a + 1, t.b - 1);
+            add(t.a, t.a, t.b, t.b);
+        }
+
+        for y in 0..size {
+            for x in 1..size {
+                grid[y * size + x] += grid[y * size + x - 1];
+            }
+        }
+        for y in 1..size {
+            for x in 0..size {
+                grid[y * size + x] += grid[(y - 1) * size + x];
+            }
+        }
+
+        let mut best: Option<(Chord, usize)> = None;
+        for x in 1..=n {
+            for y in x + 1..=n {
+                let count = grid[y * size + x] as usize;
+                if best.is_none_or(|(_, c)| count > c) {
+                    best = Some((Chord::new(x, y), count));
+                }
+            }
+        }
+        best
+    }
+
+    /// All threads cut by the given cut
+    pub fn cut_threads(&self, cut: &Chord) -> Vec<Chord> {
+        self.threads
+            .iter()
+            .copied()
+            .filter(|t| cut.cuts(t))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn examples() {
+        let art = StringArt::new(8, &[1, 5, 2, 6, 8, 4, 1, 7, 3]);
+        assert_eq!(art.diameters(), 4);
+
+        let art = StringArt::new(8, &[1, 5, 2, 6, 8, 4, 1, 7, 3, 5, 7, 8, 2]);
+        assert_eq!(art.crossings(), 21);
+
+        let art = StringArt::new(8, &[1, 5, 2, 6, 8, 4, 1, 7, 3, 6]);
+        let (cut, max) = art.best_cut().unwrap();
+        assert_eq!((cut, max), (Chord::new(3, 7), 7));
+        assert_eq!(art.cut_threads(&cut).len(), max);
+    }
+
+    #[test]
+    fn chords() {
+        assert!(Chord::new(1, 5).crosses(&Chord::new(7, 3)));
+        assert!(!Chord::new(1, 5).crosses(&Chord::new(5, 7)));
+        assert!(!Chord::new(1, 5).crosses(&Chord::new(1, 5)));
+        assert!(Chord::new(1, 5).cuts(&Chord::new(5, 1)));
+        assert!(!Chord::new(2, 3).cuts(&Chord::new(4, 8)));
+    }
+
+    #[test]
+    fn repeated_threads() {
+        // the same thread three times gives three pairs
+        let art = StringArt::new(4, &[1, 3, 1, 3]);
+        assert_eq!(art.diameters(), 3);
+        assert_eq!(art.crossings(), 3);
+        assert_eq!(art.best_cut(), Some((Chord::new(1, 3), 3)));
+
+        assert_eq!(StringArt::new(5, &[1, 3, 5]).diameters(), 0);
+        assert_eq!(StringArt::new(1, &[1]).best_cut(), None);
+    }
+
+    #[test]
+    fn matches_all_pairs() {
+        let nails = 12;
+        for k in 0..100 {
+            // walk around the circle with varying step sizes
+            let mut sequence = vec![1 + k % nails];
+            for i in 0..k % 40 {
+                let step = 1 + (i * i * 7 + k * 5) % (nails - 1);
+                sequence.push((sequence[i] - 1 + step) % nails + 1);
+            }
+            let art = StringArt::new(nails, &sequence);
+
+            let expected = (0..art.threads.len())
+                .map(|i| {
+                    art.threads[..i]
+                        .iter()
+                        .filter(|t| t.cuts(&art.threads[i]))
+                        .count() as u64
+                })
+                .sum::<u64>();
+            assert_eq!(art.crossings(), expected);
+
+            let expected = (1..=nails)
+                .flat_map(|x| (x + 1..=nails).map(move |y| Chord::new(x, y)))
+                .map(|cut| art.cut_threads(&cut).len())
+                .max();
+            assert_eq!(art.best_cut().map(|(_, max)| max), expected);
+        }
+    }
+}
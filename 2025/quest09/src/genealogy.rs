@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ParseError {
+    /// A line does not have the form `id:sequence`
+    InvalidLine(usize),
+
+    /// A sequence has a different length than the first one
+    Ragged(usize),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidLine(line) => {
+                write!(f, "line {line} is not of the form id:sequence")
+            }
+            ParseError::Ragged(line) => {
+                write!(f, "line {line} has a different length than the first line")
+            }
+        }
+    }
+}
+
+pub struct Scale {
+    pub id: usize,
+    pub dna: Vec<char>,
+}
+
+/// Parse one scale per line. Sequences may consist of arbitrary characters
+/// but must all have the same length.
+pub fn parse(input: &str) -> Result<Vec<Scale>, ParseError> {
+    let scales = input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            let (id, dna) = l.split_once(':').ok_or(ParseError::InvalidLine(i + 1))?;
+            let id = id.parse().map_err(|_| ParseError::InvalidLine(i + 1))?;
+            Ok(Scale {
+                id,
+                dna: dna.chars().collect(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(i) = scales
+        .iter()
+        .position(|s| s.dna.len() != scales[0].dna.len())
+    {
+        return Err(ParseError::Ragged(i + 1));
+    }
+    Ok(scales)
+}
+
+/// A child together with its parents (all given by their identifiers)
+pub struct Family {
+    pub child: usize,
+    pub parents: [usize; 2],
+
+    /// The product of the number of symbols the child shares with each
+    /// parent
+    pub similarity: u64,
+}
+
+/// A child for which more than one pair of scales could be the parents
+pub struct Ambiguity {
+    pub child: usize,
+    pub candidates: Vec<[usize; 2]>,
+}
+
+/// The family tree inferred from a set of scales. Two scales are the
+/// parents of a third one if every symbol of the child's sequence appears
+/// at the same position in at least one of the parents.
+pub struct Genealogy {
+    ids: Vec<usize>,
+
+    /// The valid parent pairs (as indexes) of each scale, sorted
+    candidates: Vec<Vec<[usize; 2]>>,
+
+    /// The number of symbols each scale shares with each of its parents
+    /// in the first pair of candidates
+    matches: Vec<[u64; 2]>,
+}
+
+impl Genealogy {
+    /// Infer the parents of all scales.
+    ///
+    /// For each child, we first count how many symbols every other scale
+    /// shares with it using an index of the scales by position and symbol.
+    /// Since two parents must together cover every position, the second
+    /// parent needs at least as many matches as the first one is missing.
+    /// Moreover, it must match the child at every position where the first
+    /// parent does not, so we only look at scales from the smallest index
+    /// bucket among these positions. First parents are tried in descending
+    /// order of matches, stopping once even the best match can no longer
+    /// make up for the missing positions.
+    pub fn infer(scales: &[Scale]) -> Self {
+        let len = scales.first().map_or(0, |s| s.dna.len());
+        let mut index = vec![HashMap::<char, Vec<usize>>::new(); len];
+        for (i, s) in scales.iter().enumerate() {
+            for (pos, &c) in s.dna.iter().enumerate() {
+                index[pos].entry(c).or_default().push(i);
+            }
+        }
+
+        let all = (0..scales.len()).collect::<Vec<_>>();
+        let mut candidates = Vec::with_capacity(scales.len());
+        let mut matches = Vec::with_capacity(scales.len());
+        let mut count = vec![0; scales.len()];
+        let mut order = Vec::with_capacity(scales.len());
+        let mut missing = Vec::with_capacity(len);
+        for (child, s) in scales.iter().enumerate() {
+            count.fill(0);
+            for (pos, c) in s.dna.iter().enumerate() {
+                for &other in &index[pos][c] {
+                    count[other] += 1;
+                }
+            }
+
+            order.clear();
+            order.extend((0..scales.len()).filter(|&p| p != child));
+            order.sort_unstable_by_key(|&p| std::cmp::Reverse(count[p]));
+            let max_count = order.first().map_or(0, |&p| count[p]);
+
+            let mut pairs = Vec::new();
+            for &p1 in &order {
+                // no later first parent can be completed by any other scale
+                if count[p1] + max_count < len {
+                    break;
+                }
+                missing.clear();
+                missing.extend((0..len).filter(|&pos| scales[p1].dna[pos] != s.dna[pos]));
+                let others = missing
+                    .iter()
+                    .map(|&pos| &index[pos][&s.dna[pos]])
+                    .min_by_key(|b| b.len())
+                    .unwrap_or(&all);
+                for &p2 in others {
+                    if p2 <= p1 || p2 == child || count[p2] < missing.len() {
+                        continue;
+                    }
+                    if missing.iter().all(|&pos| scales[p2].dna[pos] == s.dna[pos]) {
+                        pairs.push([p1, p2]);
+                    }
+                }
+            }
+            pairs.sort_unstable();
+
+            matches.push(pairs.first().map_or([0, 0], |p| p.map(|p| count[p] as u64)));
+            candidates.push(pairs);
+        }
+
+        Self {
+            ids: scales.iter().map(|s| s.id).collect(),
+            candidates,
+            matches,
+        }
+    }
+
+    /// All children and their parents in the order of the scales. If there
+    /// is more than one possible pair of parents, the first one (ordered by
+    /// the positions of the parents in the input) is used.
+    pub fn families(&self) -> Vec<Family> {
+        self.candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(child, pairs)| {
+                let pair = pairs.first()?;
+                Some(Family {
+                    child: self.ids[child],
+                    parents: pair.map(|p| self.ids[p]),
+                    similarity: self.matches[child][0] * self.matches[child][1],
+                })
+            })
+            .collect()
+    }
+
+    /// All children with more than one possible pair of parents
+    pub fn ambiguities(&self) -> Vec<Ambiguity> {
+        self.candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, pairs)| pairs.len() > 1)
+            .map(|(child, pairs)| Ambiguity {
+                child: self.ids[child],
+                candidates: pairs.iter().map(|p| p.map(|p| self.ids[p])).collect(),
+            })
+            .collect()
+    }
+
+    /// Group the scales into clans of scales that are related through
+    /// families. Returns the identifiers of the scales in each clan.
+    pub fn clans(&self) -> Vec<Vec<usize>> {
+        fn find(x: usize, parent: &mut [usize]) -> usize {
+            if parent[x] != x {
+                parent[x] = find(parent[x], parent);
+            }
+            parent[x]
+        }
+
+        let mut parent = (0..self.ids.len()).collect::<Vec<_>>();
+        for (child, pairs) in self.candidates.iter().enumerate() {
+            for &p in pairs.first().into_iter().flatten() {
+                let a = find(child, &mut parent);
+                let b = find(p, &mut parent);
+                parent[a] = b;
+            }
+        }
+
+        let mut clans = HashMap::<usize, Vec<usize>>::new();
+        for i in 0..self.ids.len() {
+            let root = find(i, &mut parent);
+            clans.entry(root).or_default().push(self.ids[i]);
+        }
+        let mut clans = clans.into_values().collect::<Vec<_>>();
+        clans.sort_unstable();
+        clans
+    }
+
+    /// Render the family tree in the DOT language with edges from parents
+    /// to children. Ambiguous children are drawn with a dashed outline.
+    pub fn to_dot(&self) -> String {
+        let mut result = String::from("digraph genealogy {\n");
+        for a in self.ambiguities() {
+            result.push_str(&format!("  {} [style=dashed];\n", a.child));
+        }
+        for f in self.families() {
+            for p in f.parents {
+                result.push_str(&format!("  {p} -> {};\n", f.child));
+            }
+        }
+        result.push_str("}\n");
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Check every pair of scales against every child
+    fn brute_force(scales: &[Scale]) -> Vec<Vec<[usize; 2]>> {
+        let n = scales.len();
+        (0..n)
+            .map(|child| {
+                let mut pairs = Vec::new();
+                for p1 in (0..n).filter(|&p| p != child) {
+                    for p2 in (p1 + 1..n).filter(|&p| p != child) {
+                        let covered = (0..scales[child].dna.len()).all(|i| {
+                            scales[p1].dna[i] == scales[child].dna[i]
+                                || scales[p2].dna[i] == scales[child].dna[i]
+                        });
+                        if covered {
+                            pairs.push([p1, p2]);
+                        }
+                    }
+                }
+                pairs
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ambiguities() {
+        let scales = parse("1:AACC\n2:CCAA\n3:AAAA\n4:ACAA").unwrap();
+        let genealogy = Genealogy::infer(&scales);
+        assert_eq!(genealogy.candidates, brute_force(&scales));
+
+        let ambiguities = genealogy.ambiguities();
+        assert_eq!(ambiguities.len(), 2);
+        assert_eq!(ambiguities[0].child, 3);
+        assert_eq!(ambiguities[0].candidates, [[1, 2], [1, 4]]);
+        assert_eq!(ambiguities[1].child, 4);
+        assert_eq!(ambiguities[1].candidates, [[1, 2], [2, 3]]);
+
+        // the first pair of candidates is used for the families
+        let families = genealogy.families();
+        assert_eq!(families.len(), 2);
+        assert_eq!((families[0].child, families[0].parents), (3, [1, 2]));
+        assert_eq!(families[0].similarity, 2 * 2);
+        assert_eq!((families[1].child, families[1].parents), (4, [1, 2]));
+        assert_eq!(families[1].similarity, 3);
+
+        let dot = genealogy.to_dot();
+        assert!(dot.contains("  3 [style=dashed];\n"));
+        assert!(dot.contains("  1 -> 4;\n  2 -> 4;\n"));
+    }
+
+    #[test]
+    fn pruning_matches_brute_force() {
+        // founders with scrambled sequences and children that take each
+        // symbol from one of two earlier scales
+        let mut scales = Vec::<Scale>::new();
+        for i in 0..60 {
+            let dna = if i < 12 {
+                (0..16)
+                    .map(|j| b"ACGT"[(i * i + 3 * j + i * j / 5) % 4] as char)
+                    .collect()
+            } else {
+                let (a, b) = ((i * 7) % i, (i * 13 + 5) % i);
+                (0..16)
+                    .map(|j| scales[if (i + j * j) % 3 == 0 { a } else { b }].dna[j])
+                    .collect()
+            };
+            scales.push(Scale { id: i + 1, dna });
+        }
+        let genealogy = Genealogy::infer(&scales);
+        assert_eq!(genealogy.candidates, brute_force(&scales));
+        let families = genealogy.families();
+        assert!(!families.is_empty());
+        assert!(
+            families
+                .iter()
+                .all(|f| f.parents[0] != f.parents[1] && !f.parents.contains(&f.child))
+        );
+    }
+}